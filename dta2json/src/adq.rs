@@ -2,6 +2,8 @@ use gurpschgen_lib::adq::Adq;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+static RX_PKG_COST: Lazy<Regex> = Lazy::new(||Regex::new(r"^\s*(?<points>[-+]?\d+(?:\.\d+)?)\s*(?:/\s*(?<cash>[-+]?\d+(?:\.\d+)?))?\s*$").unwrap());
+static RX_GIVES: Lazy<Regex> = Lazy::new(||Regex::new(r"^\s*(?<name>.*?)\s*(?:@\s*(?<qty>\d+))?\s*$").unwrap());
 static RX_ADQ: Lazy<Regex> = Lazy::new(||Regex::new(r"^\s*((?<c1>[-+]?\d+)\s*/\s*(?<c2>[-+]?\d+)|(?<c3>[-]?\d+))(?:\s*;\s*(?:(?<maxlvl>\d+)?(?:\s*;\s*(?:(?<bonus>[^;]*)(?:\s*;\s*(?:(?<given>[^;]*)(?:;\s*(?<modgr>[^;]*)?)?)?)?)?)?)?)?").unwrap());
 
 pub(crate) fn adq_from_tuple(value: (&str, &str)) -> Adq {
@@ -66,18 +68,77 @@ pub(crate) fn adq_from_tuple(value: (&str, &str)) -> Adq {
             given,
             mod_groups,
             level: 0,
+            equipment_given: vec![],
+            cash_cost: 0.0,
         }
     } else {
         panic!("FATAL: malformed ADQ {:?} {:?}", value.0, value.1)
     }
 }
 
+/**
+ Split a comma separated `field` into trimmed, non-empty parts.
+ */
+fn split_list(field: Option<&str>) -> Vec<String> {
+    match field {
+        Some(f) => f.split(",")
+            .map(|x| x.trim())
+            .filter(|x| !x.is_empty())
+            .map(|x| x.to_string())
+            .collect(),
+        None => vec![]
+    }
+}
+
+/**
+ Construct a package [Adq] from `value`.
+
+ Package data layout is `point-cost/$-cost; bonuses; gives; equipment gives` where equipment is
+ listed as `name@quantity` (quantity defaulting to 1).
+ */
+pub(crate) fn package_from_tuple(value: (&str, &str)) -> Adq {
+    let name = String::from(value.0);
+    let mut fields = value.1.split(";");
+    let (initial_cost, cash_cost) = match RX_PKG_COST.captures(fields.next().unwrap_or_default()) {
+        Some(caps) => (
+            caps.name("points").unwrap().as_str().parse::<f64>().unwrap().round() as i32,
+            caps.name("cash").map_or(0.0, |c| c.as_str().parse::<f64>().unwrap())
+        ),
+        None => panic!("FATAL: cost not defined in package {:?} {:?}", value.0, value.1)
+    };
+    let bonus_mods = split_list(fields.next());
+    let given = split_list(fields.next());
+    let equipment_given = split_list(fields.next()).iter()
+        .map(|x| {
+            let caps = RX_GIVES.captures(x).unwrap();
+            // `@0` lists the equipment without giving any; keep it that way.
+            let qty = match caps.name("qty") {
+                Some(q) => q.as_str().parse::<usize>().unwrap(),
+                None => 1
+            };
+            (caps.name("name").unwrap().as_str().to_string(), qty)
+        })
+        .collect();
+
+    Adq {
+        name,
+        initial_cost,
+        cost_increment: 0,
+        max_level: 1,
+        bonus_mods,
+        given,
+        mod_groups: vec![],
+        level: 0,
+        equipment_given,
+        cash_cost,
+    }
+}
 
 #[cfg(test)]
 mod adq_tests {
     use gurpschgen_lib::misc::{leveled::Leveled, named::Named};
 
-    use crate::adq::adq_from_tuple;
+    use crate::adq::{adq_from_tuple, package_from_tuple};
 
     #[test]
     fn adq_is_constructed_from_short_real_data() {
@@ -123,4 +184,30 @@ mod adq_tests {
         assert_eq!(1, adq.given.len());
         assert_eq!(3, adq.mod_groups.len());
     }
+
+    #[test]
+    fn package_is_constructed_from_real_data() {
+        let data = "10/250; +1 ST Bonus; Gambling@0, Alertness; Broadsword@2, Dagger@3, Large knife, Dice@0";
+        let pkg = package_from_tuple(("Gambler1", data));
+        assert_eq!("Gambler1", pkg.name());
+        assert_eq!(10, pkg.initial_cost());
+        assert_eq!(vec!["+1 ST Bonus".to_string()], pkg.bonus_mods);
+        assert_eq!(2, pkg.given.len());
+        assert_eq!(&vec![
+            ("Broadsword".to_string(), 2),
+            ("Dagger".to_string(), 3),
+            ("Large knife".to_string(), 1),
+            ("Dice".to_string(), 0),
+        ], pkg.equipment_gives());
+        assert_eq!(250.0, pkg.cash_cost());
+    }
+
+    #[test]
+    fn package_without_equipment_works() {
+        let pkg = package_from_tuple(("Loser1", "-10.5/0; -1 ST Bonus; Greedy"));
+        assert_eq!(-11, pkg.initial_cost());
+        assert_eq!(1, pkg.given.len());
+        assert!(pkg.equipment_gives().is_empty());
+        assert_eq!(0.0, pkg.cash_cost());
+    }
 }
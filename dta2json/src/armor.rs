@@ -1,10 +1,10 @@
 use std::collections::HashSet;
 
-use gurpschgen_lib::{damage::{DamageResistance, PassiveDefense}, equipment::armor::Armor};
+use gurpschgen_lib::{damage::{DamageResistance, PassiveDefense}, equipment::{armor::Armor, spec::Spec}};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::{container::container_from_captures, item::RX_WT, stat::stat_from_str, RX_COST_WEIGHT};
+use crate::{container::container_from_captures, damage::damage_type_from_str, item::RX_WT, spec::{spec_from_token, spec_or_unparsed}, stat::stat_from_str, RX_COST_WEIGHT};
 
 pub(crate) static RX_IS_ARMOR: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:(?:PD|DR)\s*\d)").unwrap());
 
@@ -12,12 +12,12 @@ pub(crate) static RX_IS_ARMOR: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:(?:PD|D
  Construct [Armor] from (a complex) `value`.
  */
 pub(crate) fn armor_from_tuple(value: (&str, &str)) -> Armor {
+    static RX_VS: Lazy<Regex> = Lazy::new(||Regex::new(r"^(?<what>PD|DR)\s*(?<val>\d+)\s*(?:vs[.]?\s*(?<vs>\w+(?:\s*[/&]\s*\w+)*)|\((?<vs2>\w+(?:\s*[/&]\s*\w+)*)\))$").unwrap());
     static RX_PD: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:\s*PD\s*(?<pd>\d+))").unwrap());
     static RX_DR: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:\s*DR\s*(?<dr>\d+))").unwrap());
     static RX_COVER: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:\s*[cC]overs(?::\s*|\s+)(?<cover>(\d+-\d+|[,\s]|\d+)+))").unwrap());
     static RX_STAT: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:(?<val>[-+]\d+)\s*(?<what>DX|HT|IQ|ST))").unwrap());
     static RX_SK_AFF: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:(?<val>[-+]\d+)\s+(?<what>.*))").unwrap());
-    static RX_EXTRA: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:Face protection)").unwrap());
 
     let mut pd = None;
     let mut dr = None;
@@ -29,7 +29,10 @@ pub(crate) fn armor_from_tuple(value: (&str, &str)) -> Armor {
     let mut stats_affected = vec![];
     let mut container = None;
     let mut skills_affected = vec![];
-    let mut _extra = vec![];
+    let mut spec = Spec::default();
+    // type-specific PD/DR, applied on top of the generic ones
+    let mut pd_vs = vec![];
+    let mut dr_vs = vec![];
 
     for (index, x) in value.1.split(";").enumerate() {
         let mut x = x.trim().to_string();
@@ -44,8 +47,9 @@ pub(crate) fn armor_from_tuple(value: (&str, &str)) -> Armor {
                         if p.is_empty() {
                             continue;
                         }
-                        for c in p.split("-") {
-                            cover.insert(c.parse::<i32>().unwrap());
+                        match p.split_once("-") {
+                            Some((a, b)) => cover.extend(a.trim().parse::<i32>().unwrap()..=b.trim().parse::<i32>().unwrap()),
+                            None => { cover.insert(p.parse::<i32>().unwrap()); }
                         }
                     }
                     x = x.replace(caps.get(0).unwrap().as_str(), "");
@@ -55,7 +59,23 @@ pub(crate) fn armor_from_tuple(value: (&str, &str)) -> Armor {
                     let x = x.trim();
                     if x.is_empty() { continue; }
 
-                    if let Some(x) = RX_PD.captures(x) {
+                    if spec_from_token(x, &mut spec) {
+                        /* LC, TL, flags, etc. */
+                    } else if let Some(caps) = RX_VS.captures(x) {
+                        let val = caps.name("val").unwrap().as_str().parse::<i32>().unwrap();
+                        let vs = caps.name("vs").or(caps.name("vs2")).unwrap().as_str();
+                        // A clause with an unknown damage type is kept as-is rather than half-applied.
+                        let Some(types) = vs.split(['/', '&']).map(damage_type_from_str).collect::<Option<Vec<_>>>() else {
+                            spec.unparsed.push(x.to_string());
+                            continue;
+                        };
+                        for t in types {
+                            match caps.name("what").unwrap().as_str() {
+                                "PD" => pd_vs.push((t, val)),
+                                _ => dr_vs.push((t, val))
+                            }
+                        }
+                    } else if let Some(x) = RX_PD.captures(x) {
                         pd = PassiveDefense::from(x.name("pd").unwrap().as_str().parse::<i32>().unwrap()).into()
                     } else if let Some(x) = RX_DR.captures(x) {
                         dr = DamageResistance::from(x.name("dr").unwrap().as_str().parse::<i32>().unwrap()).into()
@@ -71,12 +91,10 @@ pub(crate) fn armor_from_tuple(value: (&str, &str)) -> Armor {
                             x.name("what").unwrap().as_str().trim().to_string(),
                             x.name("val").unwrap().as_str().parse::<i32>().unwrap()
                         ))
-                    } else if let Some(_) = RX_EXTRA.captures(x) {
-                        _extra.push(x.trim().to_string())
                     } else if x.starts_with("3") || x.trim().eq("Covers:") {
                         /* no op */
                     } else {
-                        todo!("{x} --?!")
+                        spec_or_unparsed(x, &mut spec)
                     }
                 }
             },
@@ -99,14 +117,21 @@ pub(crate) fn armor_from_tuple(value: (&str, &str)) -> Armor {
             4 => for x in x.split(",") {
                 mod_groups.push(x.to_string())
             },
-            3|5 => if !x.is_empty() {
-                todo!("3|5 → \"{x}\" ?!")
-            }
+            3|5 => for x in x.split(",") {
+                spec_or_unparsed(x, &mut spec)
+            },
             _ => ()
         }
     }
 
-    Armor {_extra,
+    if !pd_vs.is_empty() {
+        pd = pd_vs.into_iter().fold(pd.unwrap_or_default(), |pd, (t, v)| pd.vs(t, v)).into()
+    }
+    if !dr_vs.is_empty() {
+        dr = dr_vs.into_iter().fold(dr.unwrap_or_default(), |dr, (t, v)| dr.vs(t, v)).into()
+    }
+
+    Armor {spec: spec.into(),
         name: value.0.trim().to_string(), skill,
         dr, pd, cover, cost, weight, mod_groups,
         stats_affected, container, skills_affected,
@@ -115,7 +140,9 @@ pub(crate) fn armor_from_tuple(value: (&str, &str)) -> Armor {
 
 #[cfg(test)]
 mod armor_tests {
-    use gurpschgen_lib::{equipment::item::container::Container, misc::costly::Costly, skill::Stat};
+    use std::collections::HashSet;
+
+    use gurpschgen_lib::{damage::DamageType, equipment::item::container::Container, misc::costly::Costly, skill::Stat};
 
     use crate::armor::armor_from_tuple;
 
@@ -139,7 +166,9 @@ mod armor_tests {
 
         assert_eq!(3, armor.mod_groups.len());
 
-        assert_eq!(vec![(Stat::DX, -1)], armor.stats_affected)
+        assert_eq!(vec![(Stat::DX, -1)], armor.stats_affected);
+
+        assert_eq!(HashSet::from([6, 8, 9, 10, 11, 12, 13, 14, 17, 18]), armor.cover)
     }
 
     #[test]
@@ -169,4 +198,22 @@ mod armor_tests {
         let c = armor_from_tuple(value);
         assert_eq!(Some(Container::Wt(40)), c.container);
     }
+
+    #[test]
+    fn typed_dr_works() {
+        let value = ("Kevlar vest", "DR 2 vs Imp, PD 2, DR 4, Covers: 9-11; 220,5.0");
+        let a = armor_from_tuple(value);
+        let dr = a.dr.unwrap();
+        assert_eq!(4, dr.against(DamageType::Cr));
+        assert_eq!(2, dr.against(DamageType::Imp));
+        assert_eq!(2, a.pd.unwrap().against(DamageType::Imp));
+    }
+
+    #[test]
+    fn unknown_damage_type_is_unparsed() {
+        let value = ("Odd vest", "DR 3 vs Ether, DR 4, Covers: 9-11; 220,5.0");
+        let a = armor_from_tuple(value);
+        assert_eq!(4, a.dr.unwrap().against(DamageType::Imp));
+        assert_eq!(vec!["DR 3 vs Ether".to_string()], a.spec.unparsed);
+    }
 }
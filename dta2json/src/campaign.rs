@@ -0,0 +1,47 @@
+use std::path::{Path, PathBuf};
+
+use gurpschgen_lib::{config::Config, dta::genre::Genre};
+
+/**
+ Check a campaign config file: that it loads, and that its genre knows whatever it allows or forbids.
+
+ The genre is looked for where we are (i.e. among the datafiles), then next to the campaign file.
+
+ **Returns** names the genre doesn't know of &ndash; or what's wrong with the config.
+ */
+pub(crate) fn check_campaign(path: &Path, verbose: bool) -> Result<Vec<String>, String> {
+    let config = Config::load(path)?;
+    if verbose {println!("Campaign: {:?}, TL{}, {} pts", config.edition, config.tl, config.points);}
+    let Some(genre) = &config.genre else { return Ok(vec![]) };
+    let genre = [PathBuf::from(genre), path.with_file_name(genre)].into_iter()
+        .find(|p| p.is_file())
+        .ok_or(format!("{}: genre \"{genre}\" not found", path.display()))?;
+    let genre = Genre::load(&genre);
+    Ok(config.allowed.iter().chain(config.forbidden.iter())
+        .filter(|name| genre.items.keys().all(|ctx| genre.find(ctx, name).is_none()))
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod campaign_tests {
+    use std::{env, fs};
+
+    use super::check_campaign;
+
+    #[test]
+    fn bad_campaigns_are_errors() {
+        let dir = env::temp_dir();
+        let broken = dir.join("dta2json-broken.campaign.toml");
+        fs::write(&broken, "edition = \"Ed5\"\n").unwrap();
+        let lost = dir.join("dta2json-lost.campaign.toml");
+        fs::write(&lost, "edition = \"Ed4\"\ntl = 8\npoints = 100\ngenre = \"nowhere.genre\"\n").unwrap();
+        let broken_result = check_campaign(&broken, false);
+        let lost_result = check_campaign(&lost, false);
+        fs::remove_file(&broken).ok();
+        fs::remove_file(&lost).ok();
+        assert!(broken_result.is_err());
+        assert!(lost_result.unwrap_err().contains("nowhere.genre"));
+        assert!(check_campaign(&dir.join("dta2json-missing.campaign.toml"), false).is_err());
+    }
+}
@@ -1,11 +1,11 @@
 use gurpschgen_lib::{context::Context, misc::category::CategoryPayload};
 
-use crate::{adq::adq_from_tuple, equipment::equipment_from_tuple, skill::{skill_from_tuple, RX_SIMPLE}};
+use crate::{adq::{adq_from_tuple, package_from_tuple}, equipment::equipment_from_tuple, modifier::modifier_from_tuple, skill::{skill_from_tuple, RX_SIMPLE}};
 
 pub(crate) fn category_payload_from_triple(value: (&Context, &str, &str)) -> CategoryPayload {
     match value.0 {
-        Context::Advantage |
-        Context::Package   => CategoryPayload::Advantage(adq_from_tuple((value.1, value.2))),
+        Context::Advantage => CategoryPayload::Advantage(adq_from_tuple((value.1, value.2))),
+        Context::Package => CategoryPayload::Package(package_from_tuple((value.1, value.2))),
         Context::Disadvantage => CategoryPayload::Disadvantage(adq_from_tuple((value.1, value.2))),
         Context::Quirk => {
             if let Some(cap) = RX_SIMPLE.captures(value.1) {
@@ -17,7 +17,7 @@ pub(crate) fn category_payload_from_triple(value: (&Context, &str, &str)) -> Cat
         Context::Equipment => CategoryPayload::Equipment(equipment_from_tuple((value.1, value.2))),
         //Context::Genre => CategoryPayload::Genre(genre_from_tuple((value.2))),
         Context::Bonus => CategoryPayload::Bonus(value.1.to_string()),
-        Context::Modifier => CategoryPayload::Modifier(value.1.to_string()),
+        Context::Modifier => CategoryPayload::Modifier(modifier_from_tuple((value.1, value.2))),
         Context::Skill |
         Context::Spell => CategoryPayload::Skill(skill_from_tuple((value.1, value.2))),
         Context::Counter => CategoryPayload::Counter(value.1.to_string()),
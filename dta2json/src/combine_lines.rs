@@ -1,39 +1,65 @@
-use std::io::{BufReader, Lines, Read, Result};
+use std::io::{BufRead, Result};
 
+use gurpschgen_lib::dta::read_lines::{DtaLine, DtaReader};
 use regex::Regex;
 
 /**
  Combine lines.
+
+ Lines ending with `\` are continued on the next line. A combined line keeps the
+ (physical) line number of its first part.
  */
-pub fn combine_lines<R>(lines: Result<Lines<BufReader<R>>>) -> Vec<String>
-where R: Sized + Read
+pub fn combine_lines<R>(lines: Result<DtaReader<R>>) -> Vec<DtaLine>
+where R: BufRead
 {
     let rxline = Regex::new(r"^(?<line>.*)\\$").unwrap();
     if let Ok(lines) = lines {
-        let mut combined_lines = vec![];
-        let mut curr_line = String::from("");
+        let mut combined_lines: Vec<DtaLine> = vec![];
+        let mut curr_line: Option<DtaLine> = None;
+        let mut last_line = 0;
         for line in lines {
-            if let Ok(line) = line {
-                if let Some(x) = rxline.captures(line.as_str()) {
-                    let l = x.name("line").unwrap().as_str();
-                    if !curr_line.is_empty() {
-                        curr_line += l
-                    } else {
-                        curr_line = l.to_string()
-                    }
-                } else {
-                    if !curr_line.is_empty() {
-                        curr_line += line.as_str();
-                        combined_lines.push(curr_line);
-                        curr_line = String::from("");
+            match line {
+                Ok(line) => {
+                    last_line = line.number;
+                    if let Some(x) = rxline.captures(line.text.as_str()) {
+                        let l = x.name("line").unwrap().as_str();
+                        match curr_line.as_mut() {
+                            Some(c) => c.text += l,
+                            None => curr_line = Some(DtaLine { number: line.number, text: l.to_string() })
+                        }
+                    } else if let Some(mut c) = curr_line.take() {
+                        c.text += line.text.as_str();
+                        combined_lines.push(c);
                     } else {
                         combined_lines.push(line)
                     }
-                }
-            } else {
-                panic!("FATAL: Something wrong in the neighborhood... or rather, a file error.")
+                },
+                Err(e) => panic!("FATAL: read error after line {last_line}: {e}")
             }
         }
+        // Dangling continuation at EOF?
+        if let Some(c) = curr_line {
+            combined_lines.push(c)
+        }
         combined_lines
     } else {vec![]}
 }
+
+#[cfg(test)]
+mod combine_lines_tests {
+    use std::io::Cursor;
+
+    use gurpschgen_lib::dta::read_lines::DtaReader;
+
+    use super::combine_lines;
+
+    #[test]
+    fn continuation_keeps_line_numbers() {
+        let raw = "type package\r\n category Classes\r\n  Cleric; 0/0; ; Clerical Magic,\\\r\n    Duty: Church <=6\r\n  Mage; 0/0; ; Literacy\r\n";
+        let lines = combine_lines(Ok(DtaReader::new(Cursor::new(raw))));
+        assert_eq!(4, lines.len());
+        assert_eq!(3, lines[2].number);
+        assert_eq!("  Cleric; 0/0; ; Clerical Magic,    Duty: Church <=6", lines[2].text);
+        assert_eq!(5, lines[3].number);
+    }
+}
@@ -2,11 +2,31 @@ use gurpschgen_lib::damage::{Damage, DamageDelivery, DamageType};
 
 use crate::{ranged::RX_R_SPEC_DMG, weapon::RX_DMGD};
 
-pub(crate) fn damage_from_str(value: &str) -> Damage {
+/**
+ Get [DamageType] from its (case-insensitive) DTA abbreviation, e.g. "Imp".
+ */
+pub(crate) fn damage_type_from_str(value: &str) -> Option<DamageType> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "cut" => Some(DamageType::Cut),
+        "cr" => Some(DamageType::Cr),
+        "imp" => Some(DamageType::Imp),
+        "var" => Some(DamageType::Var),
+        "spec" => Some(DamageType::Spec),
+        "energy" => Some(DamageType::Energy),
+        _ => None
+    }
+}
+
+/**
+ Construct [Damage] from `value`, e.g. "Cut/Sw+1".
+
+ **Returns** `None` if the damage type (or model) is unknown &ndash; the caller gets to record `value` as unparsed.
+ */
+pub(crate) fn damage_from_str(value: &str) -> Option<Damage> {
     //
     // Let's attempt to deal with damage...
     //
-    if let Some(caps) = RX_R_SPEC_DMG.captures(value) {
+    Some(if let Some(caps) = RX_R_SPEC_DMG.captures(value) {
         let dmgvar = if let Some(x) = caps.name("specvar") {
             DamageDelivery::Dice(x.as_str().parse::<i32>().unwrap(), 0)
         } else {
@@ -14,17 +34,7 @@ pub(crate) fn damage_from_str(value: &str) -> Damage {
         };
         damage_from_tuple((DamageType::Spec, dmgvar))
     } else if let Some(caps) = RX_DMGD.captures(value) {
-        let dmgtype = match caps.name("dtype").unwrap().as_str() {
-            "cut" |
-            "Cut" => DamageType::Cut,
-            "cr"  |
-            "Cr"  => DamageType::Cr,
-            "imp" |
-            "Imp" => DamageType::Imp,
-            "var" |
-            "Var" => DamageType::Var,
-            n => todo!("dtype \"{n}\" not implemented!")
-        };
+        let dmgtype = damage_type_from_str(caps.name("dtype").unwrap().as_str())?;
         
         // Deal with delivery method, if present:
         if let Some(mode) = caps.name("ddel") {
@@ -78,8 +88,8 @@ pub(crate) fn damage_from_str(value: &str) -> Damage {
     // Utterly unknown dmg model?!
     //
     else {
-        todo!("What to do with \"{value}\"?!")
-    }
+        return None
+    })
 }
 
 /**
@@ -102,45 +112,50 @@ mod damage_tests {
 
     use super::{Damage, DamageDelivery};
 
+    #[test]
+    fn unknown_dtype_is_none() {
+        assert_eq!(None, damage_from_str("Burn/2d"));
+    }
+
     #[test]
     fn cr_sw_works() {
         let data = "Cr/Sw+2";
-        let dmg = damage_from_str(data);
+        let dmg = damage_from_str(data).unwrap();
         assert_eq!(Damage::Cr(DamageDelivery::Sw(2)), dmg);
     }
 
     #[test]
     fn cut_thr_works() {
         let data = "Cut/Thr-1";
-        let dmg = damage_from_str(data);
+        let dmg = damage_from_str(data).unwrap();
         assert_eq!(Damage::Cut(DamageDelivery::Thr(-1)), dmg);
     }
 
     #[test]
     fn imp_dice_works() {
         let data = "Imp/1d-2";
-        let dmg = damage_from_str(data);
+        let dmg = damage_from_str(data).unwrap();
         assert_eq!(Damage::Imp(DamageDelivery::Dice(1, -2)), dmg);
     }
 
     #[test]
     fn cut_flatdmg_works() {
         let data = "Cut/10";
-        let dmg = damage_from_str(data);
+        let dmg = damage_from_str(data).unwrap();
         assert_eq!(Damage::Cut(DamageDelivery::Flat(10)), dmg);
     }
 
     #[test]
     fn cut_10d_works() {
         let data = "Cut/10d";
-        let dmg = damage_from_str(data);
+        let dmg = damage_from_str(data).unwrap();
         assert_eq!(Damage::Cut(DamageDelivery::Dice(10, 0)), dmg);
     }
 
     #[test]
     fn cr_dice_works() {
         let data = "Cr/2+1";
-        let dmg = damage_from_str(data);
+        let dmg = damage_from_str(data).unwrap();
         assert_eq!(Damage::Cr(DamageDelivery::Dice(2, 1)), dmg);
     }
 
@@ -148,7 +163,7 @@ mod damage_tests {
     #[should_panic]
     fn crx_dice_fails() {
         let data = "Crx/66+6";
-        let dmg = damage_from_str(data);
+        let dmg = damage_from_str(data).unwrap();
         assert_eq!(Damage::Cr(DamageDelivery::Flat(6)), dmg);
     }
 }
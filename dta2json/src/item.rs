@@ -1,9 +1,12 @@
-use gurpschgen_lib::equipment::item::Item;
+use gurpschgen_lib::equipment::{item::Item, spec::Spec};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::{container::container_from_captures, spec::spec_from_notes};
+
 static RX_ITEM: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:^\s*(?<notes>[^;]*)?(?:;\s*(?:(?<cost>\d+([.]?\d+)?)(?:\s*,\s*(?<wt>\d+([.]?\d+)?))?(?:;\s*(?:(?<skill>[^;]*)?(?:;\s*((?:[^;]*)?(?:;\s*(?<modgr>[^;]*)?)?)?)?)?)?)?)?)").unwrap());
 pub(crate) static RX_WT: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:(?<lbs>\d+)\s*lbs?[.]?)").unwrap());
+static RX_HOLDS: Lazy<Regex> = Lazy::new(||Regex::new(r"^(?:[hH]olds\s+(?<lbs>\d+)\s*lbs?[.]?|(?:[hH]olds\s+)?(?<qt>\d+)\s*(?:quarts?|qts?[.]?))$").unwrap());
 
 pub(crate) fn item_from_tuple(value: (&str, &str)) -> Item {
     let mut notes = None;
@@ -11,12 +14,15 @@ pub(crate) fn item_from_tuple(value: (&str, &str)) -> Item {
     let mut weight = None;
     let mut skill = None;
     let mut mod_groups = vec![];
+    let mut spec = Spec::default();
+    let mut container = None;
     if let Some(caps) = RX_ITEM.captures(value.1) {
         // notes
         if let Some(cap) = caps.name("notes") {
-            let x = cap.as_str().trim();
-            if !x.is_empty() {
-                notes = Some(x.to_string())
+            notes = spec_from_notes(cap.as_str(), &mut spec);
+            // a container?
+            if let Some(n) = &notes {
+                container = n.split(",").find_map(|x| RX_HOLDS.captures(x.trim())).map(container_from_captures);
             }
         }
         
@@ -55,11 +61,13 @@ pub(crate) fn item_from_tuple(value: (&str, &str)) -> Item {
         }
     };
 
-    Item { name: value.0.to_string(), notes, cost, weight, skill, mod_groups, }
+    Item { name: value.0.to_string(), notes, cost, weight, skill, mod_groups, container, spec: spec.into(), }
 }
 
 #[cfg(test)]
 mod item_tests {
+    use gurpschgen_lib::equipment::item::container::Container;
+
     use crate::item::item_from_tuple;
 
     #[test]
@@ -72,4 +80,11 @@ mod item_tests {
         assert_eq!("Bicycling", item.skill.unwrap().as_str());
         assert_eq!(3, item.mod_groups.len());
     }
+
+    #[test]
+    fn containers_work() {
+        assert_eq!(Some(Container::Liquid(100)), item_from_tuple(("Tank", "100 quarts; 50, 10")).container);
+        assert_eq!(Some(Container::Wt(20)), item_from_tuple(("Sack", "Holds 20 lbs; 1, 0.5")).container);
+        assert_eq!(None, item_from_tuple(("Rock", "weighs 3000 pounds; 0, 3000")).container);
+    }
 }
@@ -12,7 +12,9 @@ mod skill;
 mod context;
 mod categorypayload;
 mod adq;
+mod modifier;
 pub(crate) mod equipment;
+mod spec;
 mod ranged;
 mod rof;
 mod shots;
@@ -25,29 +27,79 @@ mod container;
 mod stat;
 mod difficultyrating;
 mod skillroot;
+mod meta;
+mod watch;
+mod campaign;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use campaign::check_campaign;
 use clap::Parser;
-use gurpschgen_lib::dta::{locate_dta::locate_dta, read_lines::read_lines};
+use gurpschgen_lib::dta::{locate_dta::{locate_dta, try_locate_dta}, read_lines::read_dta};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use verify_dta::verify_and_categorize_dta;
+use watch::watch;
 
 static RX_COST_WEIGHT: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:\s*(?<cost>\d+(?:[.]\d+)?)(?:\s*,\s*(?<wt>\d+(?:[.]\d+)?))?)").unwrap());
 
 #[derive(Parser)]
 struct Cli {
-    path: PathBuf,
+    /// DTA/GEN file to convert &ndash; or with `--watch`, the directory to watch.
+    #[arg(required_unless_present_any = ["watch", "campaign"])]
+    path: Option<PathBuf>,
     verbose: Option<bool>,
+    /// Watch a data directory and reconvert DTA/GEN files into JSON as they change.
+    #[arg(long)]
+    watch: bool,
+    /// With `--watch`, validate the affected `.genre` files after each change.
+    #[arg(long, requires = "watch")]
+    validate: bool,
+    /// Check a campaign config file (JSON/TOML) against its genre.
+    #[arg(long)]
+    campaign: Option<PathBuf>,
+}
+
+/**
+ Pin down `path` as given on the command line, i.e. relative to where we were started from &ndash;
+ [locate_dta] moves elsewhere. Paths which don't exist here are left as-is, to be found among the datafiles.
+ */
+fn resolve(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or(path.to_path_buf())
 }
 
 fn main() {
     let args = Cli::parse();
     let verbose = if let Some(v) = args.verbose {v} else {false};
     if verbose {println!("GURPS .DTA/.GEN → JSON Converter");}
-    locate_dta(verbose);
-    let dump = verify_and_categorize_dta(&args.path, read_lines(args.path.clone()), verbose);
+    let path = args.path.as_deref().map(resolve);
+    if let Some(campaign) = args.campaign.as_deref().map(resolve) {
+        // The campaign's genre is likely among the datafiles, but it need not be.
+        try_locate_dta(verbose);
+        match check_campaign(&campaign, verbose) {
+            Ok(unknown) => for name in unknown {
+                eprintln!("Warning: campaign refers to \"{name}\", which its genre doesn't know.")
+            },
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1)
+            }
+        }
+        return;
+    }
+    if args.watch {
+        // An explicitly given directory is all we need.
+        if path.is_none() {
+            locate_dta(verbose);
+        }
+        watch(&path.unwrap_or(PathBuf::from(".")), args.validate, verbose)
+    }
+    let path = path.unwrap();
+    // Resolved paths don't mind where we are; others are relative to the datafiles.
+    if path.is_relative() {
+        locate_dta(verbose);
+    }
+    let dump = verify_and_categorize_dta(&path, read_dta(path.clone()), verbose);
     println!("{}", serde_json::to_string(&dump).unwrap());
 }
 
@@ -55,7 +107,7 @@ fn main() {
 mod main_tests {
     use std::path::PathBuf;
 
-    use gurpschgen_lib::dta::{locate_dta::locate_dta, read_lines::read_lines};
+    use gurpschgen_lib::dta::{locate_dta::locate_dta, read_lines::read_dta};
 
     use crate::verify_dta::verify_and_categorize_dta;
 
@@ -64,8 +116,8 @@ mod main_tests {
         let verbose = false;
         let path = PathBuf::from("_x.dump");
         locate_dta(verbose);
-        let content = verify_and_categorize_dta(&path, read_lines(path.clone()), verbose);
-        for x in content {
+        let content = verify_and_categorize_dta(&path, read_dta(path.clone()), verbose);
+        for x in content.items {
             println!("{}", x.0)
         }
     }
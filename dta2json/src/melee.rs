@@ -1,10 +1,84 @@
-use gurpschgen_lib::{damage::DamageDelivery, equipment::weapon::melee::Melee};
+use gurpschgen_lib::{damage::DamageDelivery, equipment::weapon::melee::{parry::Parry, reach::Reach, Melee}};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::{damage::damage_from_str, ranged::RX_R_ACC, weapon::{RX_DMGD, RX_MAX_DMG}, RX_COST_WEIGHT};
+use gurpschgen_lib::equipment::spec::Spec;
+
+use crate::{damage::damage_from_str, ranged::RX_R_ACC, spec::{spec_from_notes, spec_or_unparsed}, weapon::{RX_DMGD, RX_MAX_DMG}, RX_COST_WEIGHT};
 
 static RX_R_ST: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:ST\s*(?<st>\d+))").unwrap());
+static RX_REACH: Lazy<Regex> = Lazy::new(||Regex::new(r"(?i)\breach\s+(?<reach>[c\d]+(?:\s*[-,]\s*[c\d]+)*)\*?").unwrap());
+static RX_PARRY: Lazy<Regex> = Lazy::new(||Regex::new(r"(?i)^parry\s*(?:(?<no>no)|(?<pmod>[-+]?\d+)?\s*(?<kind>[fu])?)$").unwrap());
+static RX_TWO_HANDED: Lazy<Regex> = Lazy::new(||Regex::new(r"(?i)^(?:(?:2|two)[- ]?hand(?:ed)?|2H)$").unwrap());
+
+/**
+ Handling characteristics of a melee weapon.
+ */
+#[derive(Default)]
+struct Handling {
+    reach: Vec<Reach>,
+    parry: Parry,
+    two_handed: bool,
+}
+
+impl Handling {
+    /**
+     Pick reach out of `value`, e.g. "Reach 1,2" or "Reach C-2".
+
+     **Returns** `value` with reach removed &ndash; reach has commas of its own, so it's dealt with before splitting.
+     Malformed reach (e.g. "Reach 3-C") goes to `spec`'s unparsed bin.
+     */
+    fn take_reach(&mut self, value: &str, spec: &mut Spec) -> String {
+        let Some(caps) = RX_REACH.captures(value) else {
+            return value.to_string()
+        };
+        let reach = |x: &str| if x.eq_ignore_ascii_case("c") { Some(Reach::C) } else { x.parse::<i32>().ok().map(Reach::Yards) };
+        let part = |x: &str| match x.split_once("-") {
+            Some((a, b)) => {
+                let (a, Reach::Yards(to)) = (reach(a.trim())?, reach(b.trim())?) else { return None };
+                let from = if let Reach::Yards(x) = a { x } else { 0 };
+                Some((a == Reach::C).then_some(Reach::C).into_iter().chain((from.max(1)..=to).map(Reach::Yards)).collect())
+            },
+            None => Some(vec![reach(x.trim())?])
+        };
+        match caps.name("reach").unwrap().as_str().split(",").map(part).collect::<Option<Vec<Vec<Reach>>>>() {
+            Some(reaches) => self.reach.extend(reaches.into_iter().flatten()),
+            None => spec.unparsed.push(caps.get(0).unwrap().as_str().trim().to_string())
+        }
+        self.reach.sort();
+        self.reach.dedup();
+        RX_REACH.replace(value, "").to_string()
+    }
+
+    /**
+     See if `token` is about parrying or handedness; record it if so.
+     */
+    fn take_token(&mut self, token: &str) -> bool {
+        let modifier = match self.parry {
+            Parry::Yes(x) | Parry::Fencing(x) | Parry::Unbalanced(x) => x,
+            Parry::No => 0
+        };
+        if let Some(caps) = RX_PARRY.captures(token) {
+            let modifier = caps.name("pmod").map_or(0, |x| x.as_str().parse::<i32>().unwrap());
+            self.parry = if caps.name("no").is_some() { Parry::No } else {
+                match caps.name("kind").map(|x| x.as_str().to_ascii_uppercase()).as_deref() {
+                    Some("F") => Parry::Fencing(modifier),
+                    Some("U") => Parry::Unbalanced(modifier),
+                    _ => Parry::Yes(modifier)
+                }
+            }
+        } else if token.eq_ignore_ascii_case("unbalanced") {
+            self.parry = Parry::Unbalanced(modifier)
+        } else if token.eq_ignore_ascii_case("fencing") {
+            self.parry = Parry::Fencing(modifier)
+        } else if RX_TWO_HANDED.is_match(token) {
+            self.two_handed = true
+        } else {
+            return false
+        }
+        true
+    }
+}
 
 /**
  Construct a melee weapon from given `value`.
@@ -21,10 +95,15 @@ pub(crate) fn melee_wpn_from_tuple(value: (&str, &str)) -> Melee {
     let mut mod_groups = vec![];
     let mut st_req = None;
     let mut max_damage = None;
+    let mut spec = Spec::default();
+    let mut handling = Handling::default();
     for (index, x) in value.1.split(";").enumerate() {
         match index {
-            0 => for d in x.split(",") {
+            0 => for d in handling.take_reach(x, &mut spec).split(",") {
                 let d = d.trim();
+                if d.is_empty() || handling.take_token(d) {
+                    continue;
+                }
                 if let Some(x) = RX_R_ACC.captures(d) {
                     acc = x.name("acc").unwrap().as_str().parse::<i32>().unwrap().into()
                 }
@@ -32,7 +111,10 @@ pub(crate) fn melee_wpn_from_tuple(value: (&str, &str)) -> Melee {
                     st_req = x.name("st").unwrap().as_str().parse::<i32>().unwrap().into()
                 }
                 else if let Some(_) = RX_DMGD.captures(d) {
-                    damage.push(damage_from_str(d.trim()))
+                    match damage_from_str(d) {
+                        Some(dmg) => damage.push(dmg),
+                        None => spec.unparsed.push(d.to_string())
+                    }
                 }
                 else if let Some(x) = RX_MAX_DMG.captures(d) {
                     max_damage = Some(DamageDelivery::Dice(
@@ -41,6 +123,9 @@ pub(crate) fn melee_wpn_from_tuple(value: (&str, &str)) -> Melee {
                             x.as_str().parse::<i32>().unwrap()
                         } else {0}))
                 }
+                else {
+                    spec_or_unparsed(d, &mut spec)
+                }
             },
             1 => if let Some(cap) = RX_COST_WEIGHT.captures(x) {
                 if let Some(c) = cap.name("cost") {
@@ -58,10 +143,9 @@ pub(crate) fn melee_wpn_from_tuple(value: (&str, &str)) -> Melee {
                 }
             },
             3 => {
-                let x = x.trim();
-                if !x.is_empty() {
-                    notes = x.to_string().into()
-                }
+                let x = handling.take_reach(x, &mut spec);
+                let rest: Vec<&str> = x.split(",").map(|t| t.trim()).filter(|t| !handling.take_token(t)).collect();
+                notes = spec_from_notes(&rest.join(","), &mut spec)
             },
             4 => {
                 for x in x.split(",") {
@@ -76,12 +160,15 @@ pub(crate) fn melee_wpn_from_tuple(value: (&str, &str)) -> Melee {
         }
     }
 
-    Melee { name: value.0.trim().to_string(), damage, cost, weight, skill, notes, mod_groups, acc, st_req, max_damage }
+    Melee {
+        name: value.0.trim().to_string(), damage, cost, weight, skill, notes, mod_groups, acc, st_req, max_damage, spec: spec.into(),
+        reach: handling.reach, parry: handling.parry, two_handed: handling.two_handed,
+    }
 }
 
 #[cfg(test)]
 mod melee_tests {
-    use gurpschgen_lib::{damage::{Damage, DamageDelivery}, equipment::weapon::Weapon, misc::{costly::Costly, damaged::Damaged, noted::Noted, skilled::Skilled, weighed::Weighed}};
+    use gurpschgen_lib::{damage::{Damage, DamageDelivery}, equipment::weapon::{melee::{parry::Parry, reach::Reach}, Weapon}, misc::{costly::Costly, damaged::Damaged, noted::Noted, skilled::Skilled, weighed::Weighed}};
 
     use crate::{melee::melee_wpn_from_tuple, weapon::wpn_from_tuple};
 
@@ -125,4 +212,37 @@ mod melee_tests {
         });
         assert_eq!(DamageDelivery::Dice(1, 2), wpn.max_damage().clone().unwrap());
     }
+
+    #[test]
+    fn handling_works() {
+        let data = ("Halberd", "Cut/Sw+5, Imp/Sw+4, Reach 2,3, Parry 0U, 2-handed;150,12.0;Polearm;;Weapon");
+        let wpn = melee_wpn_from_tuple(data);
+        assert_eq!(vec![Reach::Yards(2), Reach::Yards(3)], wpn.reach);
+        assert_eq!(Parry::Unbalanced(0), wpn.parry);
+        assert!(wpn.two_handed);
+        assert_eq!(2, wpn.damage.len());
+
+        let data = ("Large knife", "Cut/Sw-2, Imp/Thr;40,1.0;Knife;Reach C-1, Parry -1, balanced for throwing;Sword Quality");
+        let wpn = melee_wpn_from_tuple(data);
+        assert_eq!(vec![Reach::C, Reach::Yards(1)], wpn.reach);
+        assert_eq!(Parry::Yes(-1), wpn.parry);
+        assert!(!wpn.two_handed);
+        assert_eq!(Some("balanced for throwing"), wpn.notes());
+
+        let data = ("Rapier", "Imp/Thr+1, Reach 1, Fencing;500,2.75;Fencing;;");
+        assert_eq!(Parry::Fencing(0), melee_wpn_from_tuple(data).parry);
+        let data = ("Whip", "Cr/Sw-2, Reach 1-7, Parry No;20,2.0;Whip;;");
+        let wpn = melee_wpn_from_tuple(data);
+        assert_eq!(Parry::No, wpn.parry);
+        assert_eq!(7, wpn.reach.len());
+    }
+
+    #[test]
+    fn malformed_reach_is_unparsed() {
+        let data = ("Odd pike", "Imp/Thr+2, 2-handed, Reach 3-C;80,9.0;Polearm;;Weapon");
+        let wpn = melee_wpn_from_tuple(data);
+        assert!(wpn.reach.is_empty());
+        assert!(wpn.two_handed);
+        assert_eq!(vec!["Reach 3-C".to_string()], wpn.spec.unparsed);
+    }
 }
@@ -0,0 +1,59 @@
+use std::{path::Path, time::{SystemTime, UNIX_EPOCH}};
+
+use gurpschgen_lib::dta::meta::DtaMeta;
+
+/**
+ Converter's own identification, e.g. "dta2json 0.2.0".
+ */
+pub(crate) fn converter() -> String {
+    format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+}
+
+/**
+ Convert days since UNIX epoch into a `YYYY-MM-DD` date (proleptic Gregorian).
+ */
+fn date_from_days(days: i64) -> String {
+    // See Howard Hinnant's "civil_from_days".
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/**
+ Today's date (UTC), `YYYY-MM-DD`.
+ */
+pub(crate) fn conversion_date() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    date_from_days((secs / 86_400) as i64)
+}
+
+/**
+ Construct the converter-provided part of [DtaMeta] for `filename`.
+ */
+pub(crate) fn meta_for(filename: &Path) -> DtaMeta {
+    DtaMeta {
+        source: filename.file_name().map(|f| f.to_string_lossy().to_string()),
+        converted: conversion_date().into(),
+        converter: converter().into(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod meta_tests {
+    use super::date_from_days;
+
+    #[test]
+    fn date_from_days_works() {
+        assert_eq!("1970-01-01", date_from_days(0));
+        assert_eq!("2000-02-29", date_from_days(11_016));
+        assert_eq!("2024-08-23", date_from_days(19_958));
+    }
+}
@@ -0,0 +1,97 @@
+use gurpschgen_lib::modifier::effect::{Effect, EffectModifier, Op, Target};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static RX_EFFECT: Lazy<Regex> = Lazy::new(||Regex::new(r"^\s*(?<op>[*xX/+-])\s*(?<val>\d*\.?\d+)\s*(?<target>.*?)\s*$").unwrap());
+
+/**
+ Get effect [Target] from its (case-insensitive) DTA name, e.g. "Point Cost" or MakeChar's "Damage Add Bonus".
+ */
+fn target_from_str(value: &str) -> Target {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" |
+        "cost" |
+        "$" |
+        "$ cost" => Target::Cost,
+        "point cost" |
+        "points" => Target::PointCost,
+        "weight" |
+        "wt" => Target::Weight,
+        "damage" |
+        "dmg" |
+        "damage bonus" |
+        "damage add bonus" => Target::Damage,
+        "dr" |
+        "dr bonus" => Target::DR,
+        "pd" |
+        "pd bonus" => Target::PD,
+        "skill" => Target::Skill,
+        _ => Target::Other(value.trim().to_string())
+    }
+}
+
+/**
+ Construct an [Effect] from `value`, e.g. "*2.5 Point Cost".
+ */
+fn effect_from_str(value: &str) -> Option<Effect> {
+    let caps = RX_EFFECT.captures(value)?;
+    let val = caps.name("val").unwrap().as_str().parse::<f64>().unwrap();
+    let op = match caps.name("op").unwrap().as_str() {
+        "/" => Op::Div(val),
+        "+" => Op::Add(val),
+        "-" => Op::Add(-val),
+        _ => Op::Mul(val),
+    };
+    Some(Effect { op, target: target_from_str(caps.name("target").unwrap().as_str()) })
+}
+
+/**
+ Construct an [EffectModifier] from `value`.
+ */
+pub(crate) fn modifier_from_tuple(value: (&str, &str)) -> EffectModifier {
+    let mut modifier = EffectModifier::new(value.0.trim());
+    for token in value.1.split([',', ';']).map(|x| x.trim()).filter(|x| !x.is_empty()) {
+        match effect_from_str(token) {
+            Some(e) => modifier.effects.push(e),
+            None => modifier.unparsed.push(token.to_string())
+        }
+    }
+    modifier
+}
+
+#[cfg(test)]
+mod modifier_tests {
+    use gurpschgen_lib::modifier::effect::{Effect, Op, Target};
+
+    use super::modifier_from_tuple;
+
+    #[test]
+    fn point_cost_works() {
+        let m = modifier_from_tuple(("real test 2", "/2.5 Point Cost;"));
+        assert_eq!("real test 2", m.name);
+        assert_eq!(vec![Effect { op: Op::Div(2.5), target: Target::PointCost }], m.effects);
+        let m = modifier_from_tuple(("<= 6", "*.51 Point Cost;"));
+        assert_eq!(vec![Effect { op: Op::Mul(0.51), target: Target::PointCost }], m.effects);
+    }
+
+    #[test]
+    fn quality_works() {
+        let m = modifier_from_tuple(("Fine", "*4 Cost, +1 Damage, -1 Skill, glows"));
+        assert_eq!(vec![
+            Effect { op: Op::Mul(4.0), target: Target::Cost },
+            Effect { op: Op::Add(1.0), target: Target::Damage },
+            Effect { op: Op::Add(-1.0), target: Target::Skill },
+        ], m.effects);
+        assert_eq!(vec!["glows".to_string()], m.unparsed);
+    }
+
+    #[test]
+    fn makechar_targets_work() {
+        let m = modifier_from_tuple(("Fine", "*4 Cost, +1 Damage Add Bonus, +2 DR Bonus"));
+        assert_eq!(vec![
+            Effect { op: Op::Mul(4.0), target: Target::Cost },
+            Effect { op: Op::Add(1.0), target: Target::Damage },
+            Effect { op: Op::Add(2.0), target: Target::DR },
+        ], m.effects);
+    }
+}
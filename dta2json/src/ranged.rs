@@ -2,7 +2,9 @@ use gurpschgen_lib::{damage::DamageDelivery, equipment::weapon::ranged::Ranged};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::{damage::damage_from_str, equipment::{RX_COUNTRY, RX_LEGALITY, RX_TL}, rof::rof_from_captures, shots::shots_from_captures, weapon::{RX_DMGD, RX_MAX_DMG}, RX_COST_WEIGHT};
+use gurpschgen_lib::equipment::spec::Spec;
+
+use crate::{damage::damage_from_str, rof::rof_from_captures, shots::shots_from_captures, spec::{spec_from_notes, spec_or_unparsed}, weapon::{RX_DMGD, RX_MAX_DMG}, RX_COST_WEIGHT};
 
 static RX_R_SS: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:SS\s*(?<ss>[-+]?\d+))").unwrap());
 pub(crate) static RX_R_ACC: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:\s*[aA]cc\s*(?<acc>[-+]?\d+)?)").unwrap());
@@ -15,7 +17,6 @@ static RX_R_MAX: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:(?:max|Max|MAX)\s+(?:
 pub(crate) static RX_R_SHOTS: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:[sS]hots\s+(?:(?:(?<battch>\d+)\/(?<batt>(?:A){1,3}|B|C|D|E|F))|(?:[(](?<fthrow1>\d+)[)])(?<fthrow2>\d+)|(?<xxxbelt>xxxB)|(?:(?<bfed>\d+)B(?<boxfed>ox)?)|(?:(?<splus>\d+)(?<splusmod>[+]\d+)?)))").unwrap());
 static RX_R_ST: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:ST\s*(?:(?<st>\d+)|XX\([tT]ripod\)))").unwrap());
 pub(crate) static RX_R_SPEC_DMG: Lazy<Regex> = Lazy::new(||Regex::new(r"(?:[sS]pec)(?:\/(?<specvar>\d+))?").unwrap());
-static RX_19XX: Lazy<Regex> = Lazy::new(||Regex::new(r"19\d\d").unwrap());
 
 /**
  Construct a ranged weapon from given `value`.
@@ -39,19 +40,18 @@ pub(crate) fn ranged_wpn_from_tuple(value: (&str, &str)) -> Ranged {
     let mut shots = None;
     let mut st_req = None;
     let mut max_damage = None;
-    let mut rl_year = None;
-    let mut rl_country = None;
     let mut tripod = false;
-    let mut tl = None;
-    let mut lc = None;
+    let mut spec = Spec::default();
     for (index, x) in value.1.split(";").enumerate() {
         match index {
             0 => for d in x.split(",") {
                 let d = d.trim();
-                if let Some(x) = RX_R_SPEC_DMG.captures(d) {
-                    damage.push(damage_from_str(x.get(0).unwrap().as_str()))
-                } else if let Some(x) = RX_DMGD.captures(d) {// TODO: this unfortunately will get repeated in Damage::from(). Fix somehow?
-                    damage.push(damage_from_str(x.get(0).unwrap().as_str()))
+                if let Some(x) = RX_R_SPEC_DMG.captures(d).or_else(|| RX_DMGD.captures(d)) {// TODO: this unfortunately will get repeated in Damage::from(). Fix somehow?
+                    let x = x.get(0).unwrap().as_str();
+                    match damage_from_str(x) {
+                        Some(dmg) => damage.push(dmg),
+                        None => spec.unparsed.push(x.to_string())
+                    }
                 } else if let Some(x) = RX_R_ACC.captures(d) {
                     acc = if let Some(x) = x.name("acc") {
                         x.as_str().parse::<i32>().unwrap()
@@ -85,16 +85,8 @@ pub(crate) fn ranged_wpn_from_tuple(value: (&str, &str)) -> Ranged {
                             x.as_str().parse::<i32>().unwrap()
                         } else {0}
                     ))
-                } else if let Some(x) = RX_19XX.captures(d) {
-                    rl_year = x.get(0).unwrap().as_str().parse::<i32>().unwrap().into()
-                } else if let Some(x) = RX_TL.captures(d) {
-                    tl = x.name("tl").unwrap().as_str().parse::<i32>().unwrap().into()
-                } else if let Some(x) = RX_LEGALITY.captures(d) {
-                    lc = x.name("lc").unwrap().as_str().parse::<i32>().unwrap().into()
-                } else if let Some(x) = RX_COUNTRY.captures(d) {
-                    rl_country = x.get(0).unwrap().as_str().to_string().into()
                 } else {
-                    todo!("Unknown: {d}")
+                    spec_or_unparsed(d, &mut spec)
                 }
             },
             1 => if let Some(cap) = RX_COST_WEIGHT.captures(x) {
@@ -112,12 +104,7 @@ pub(crate) fn ranged_wpn_from_tuple(value: (&str, &str)) -> Ranged {
                     skill = x.to_string().into()
                 }
             },
-            3 => {
-                let x = x.trim();
-                if !x.is_empty() {
-                    notes = x.to_string().into()
-                }
-            },
+            3 => notes = spec_from_notes(x, &mut spec),
             4 => {
                 for x in x.split(",") {
                     let x = x.trim();
@@ -134,7 +121,7 @@ pub(crate) fn ranged_wpn_from_tuple(value: (&str, &str)) -> Ranged {
     Ranged { name: value.0.trim().to_string(), damage, max_damage,
             cost, weight, skill, notes, mod_groups, ss, acc, rof,
             rcl, min_range, half_dmg_range, max_range, shots, st_req,
-            rl_year, rl_country, tripod, tl, lc,
+            tripod, spec: spec.into(),
     }
 }
 
@@ -182,4 +169,17 @@ mod ranged_tests {
         let wpn = ranged_wpn_from_tuple(data);
         assert_eq!(DamageDelivery::Dice(1, 2), wpn.max_damage().clone().unwrap());
     }
+
+    #[test]
+    fn spec_attributes_work() {
+        let data = ("AK-47", "Cr/5+1, Acc+7, SS 12, 1/2D 500, Max 4000, RoF 10*, Shots 30, ST 10, Rcl -2, USSR 1947, TL7, LC1, Folding stock; 300,10.5; Guns: Rifle; Uses 7.62x39mm ammo, +1 to skill");
+        let wpn = ranged_wpn_from_tuple(data);
+        assert_eq!(Some(1), wpn.spec.lc);
+        assert_eq!(Some(7), wpn.spec.tl);
+        assert_eq!(Some("USSR".to_string()), wpn.spec.country);
+        assert_eq!(Some(1947), wpn.spec.year);
+        assert_eq!(vec!["Uses 7.62x39mm ammo".to_string()], wpn.spec.ammo);
+        assert_eq!(vec!["Folding stock".to_string()], wpn.spec.unparsed);
+        assert_eq!(Some("+1 to skill".to_string()), wpn.notes);
+    }
 }
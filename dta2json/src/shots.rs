@@ -21,7 +21,11 @@ pub(crate) fn shots_from_captures(value: Captures<'_>) -> Shots {
         }
     } else if let Some(x) = value.name("battch") {
         let x = x.as_str().parse::<i32>().unwrap();
-        Shots::Battery(x, Battery::from(value.name("batt").unwrap().as_str()))
+        let batt = value.name("batt").unwrap().as_str();
+        match Battery::try_from(batt) {
+            Ok(b) => Shots::Battery(x, b),
+            Err(e) => panic!("FATAL: {e} in \"{}\"", value.get(0).unwrap().as_str())
+        }
     } else if let Some(_) = value.name("xxxbelt") {
         Shots::Belt(i32::MAX)
     } else if let Some(x) = value.name("bfed") {
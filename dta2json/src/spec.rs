@@ -0,0 +1,111 @@
+use gurpschgen_lib::equipment::spec::{Spec, SpecFlag};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static RX_TL: Lazy<Regex> = Lazy::new(||Regex::new(r"^TL\s*(?<tl>\d+)$").unwrap());
+static RX_LEGALITY: Lazy<Regex> = Lazy::new(||Regex::new(r"^LC\s*(?<lc>\d+)$").unwrap());
+static RX_ORIGIN: Lazy<Regex> = Lazy::new(||Regex::new(r"^(?<country>US(?:SR)?|BE|GE|IT|IS|GR|UK|FI|SE|NO|INT)?\s*(?<year>(?:18|19|20)\d\d)?$").unwrap());
+static RX_AMMO: Lazy<Regex> = Lazy::new(||Regex::new(r"(?i)\b(?:ammo|ammunition|rounds?|cartridges?|magazines?|clips?)\b").unwrap());
+
+/**
+ Flags by their (lowercase) DTA spelling.
+ */
+const FLAGS: [(&str, SpecFlag); 6] = [
+    ("face protection", SpecFlag::FaceProtection),
+    ("sealed", SpecFlag::Sealed),
+    ("concealable", SpecFlag::Concealable),
+    ("flexible", SpecFlag::Flexible),
+    ("disposable", SpecFlag::Disposable),
+    ("waterproof", SpecFlag::Waterproof),
+];
+
+/**
+ Try to make sense of a single spec `token` (e.g. "LC2", "TL7", "US 1986", "Face protection").
+
+ **Returns** `true` if `token` was recognized and stored in `spec`.
+ */
+pub(crate) fn spec_from_token(token: &str, spec: &mut Spec) -> bool {
+    let token = token.trim();
+    if token.is_empty() {
+        return false;
+    }
+
+    if let Some(x) = RX_LEGALITY.captures(token) {
+        spec.lc = x.name("lc").unwrap().as_str().parse::<i32>().unwrap().into()
+    } else if let Some(x) = RX_TL.captures(token) {
+        spec.tl = x.name("tl").unwrap().as_str().parse::<i32>().unwrap().into()
+    } else if let Some(x) = RX_ORIGIN.captures(token) {
+        if let Some(c) = x.name("country") {
+            spec.country = c.as_str().to_string().into()
+        }
+        if let Some(y) = x.name("year") {
+            spec.year = y.as_str().parse::<i32>().unwrap().into()
+        }
+    } else if let Some((_, flag)) = FLAGS.iter().find(|(f, _)| token.eq_ignore_ascii_case(f)) {
+        spec.set(*flag)
+    } else if RX_AMMO.is_match(token) {
+        spec.ammo.push(token.to_string())
+    } else {
+        return false;
+    }
+    true
+}
+
+/**
+ Same as [spec_from_token], but unrecognized `token` gets recorded as unparsed.
+ */
+pub(crate) fn spec_or_unparsed(token: &str, spec: &mut Spec) {
+    let token = token.trim();
+    if !token.is_empty() && !spec_from_token(token, spec) {
+        spec.unparsed.push(token.to_string())
+    }
+}
+
+/**
+ Pick spec-like parts out of (comma separated) free-form `notes`.
+
+ **Returns** whatever remains of the notes, if anything.
+ */
+pub(crate) fn spec_from_notes(notes: &str, spec: &mut Spec) -> Option<String> {
+    let rest: Vec<&str> = notes.split(",")
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty() && !spec_from_token(x, spec))
+        .collect();
+    if rest.is_empty() {
+        None
+    } else {
+        rest.join(", ").into()
+    }
+}
+
+#[cfg(test)]
+mod spec_tests {
+    use gurpschgen_lib::equipment::spec::{Spec, SpecFlag};
+
+    use super::{spec_from_notes, spec_or_unparsed};
+
+    #[test]
+    fn tokens_work() {
+        let mut spec = Spec::default();
+        for t in ["LC2", "TL 7", "USSR 1974", "Face protection", "Uses 7.62x39mm ammo", "Bayonet lug", "1986"] {
+            spec_or_unparsed(t, &mut spec)
+        }
+        assert_eq!(Some(2), spec.lc);
+        assert_eq!(Some(7), spec.tl);
+        assert_eq!(Some("USSR".to_string()), spec.country);
+        assert_eq!(Some(1986), spec.year);
+        assert!(spec.has(SpecFlag::FaceProtection));
+        assert_eq!(vec!["Uses 7.62x39mm ammo".to_string()], spec.ammo);
+        assert_eq!(vec!["Bayonet lug".to_string()], spec.unparsed);
+    }
+
+    #[test]
+    fn notes_work() {
+        let mut spec = Spec::default();
+        assert_eq!(Some("+1 to skill, Folding stock".to_string()), spec_from_notes("+1 to skill, LC3, Folding stock, UK", &mut spec));
+        assert_eq!(Some(3), spec.lc);
+        assert_eq!(Some("UK".to_string()), spec.country);
+        assert_eq!(None, spec_from_notes("TL8, LC1", &mut spec));
+        assert!(spec.unparsed.is_empty());
+    }
+}
@@ -1,10 +1,10 @@
-use std::{collections::HashMap, io::{BufReader, Lines, Read, Result}, path::PathBuf};
+use std::{collections::HashMap, io::{BufRead, Result}, path::PathBuf};
 
-use gurpschgen_lib::{context::{Context, ContextPayload}, dta::genre::Genre, misc::{category::{Category, CategoryPayload}, tl::TL}};
+use gurpschgen_lib::{context::{Context, ContextPayload}, dta::{genre::Genre, meta::DtaFile, read_lines::{DtaLine, DtaReader}}, misc::{category::{Category, CategoryPayload}, specced::Specced, tl::TL}};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::{categorypayload::category_payload_from_triple, combine_lines::combine_lines, context::context_from_str};
+use crate::{categorypayload::category_payload_from_triple, combine_lines::combine_lines, context::context_from_str, meta::meta_for};
 
 const XCG_DATA_FORMAT: &'static str = "#XCG/DATA";
 const STEVE_JACKSONS_FORMAT: &'static str = "GURPS data file (this MUST be the first line!)";
@@ -15,14 +15,17 @@ const STEVE_JACKSONS_FORMAT: &'static str = "GURPS data file (this MUST be the f
 
  *dev NOTE:* As per "official" rules, if an [Item] is reintroduced, latest data overwrites the earlier item.
 
+ Comments (`*`/`#` lines) are kept as notes: those before the first "type" go to file metadata,
+ those before a category's first item go to the category, and the rest go to the item that follows them.
+
  **Params**
  * `filename` - presumed origin of the fed lines.
  * `lines` - DTA stuff, line per line.
  
- **Returns** items categorized; [Type] → [Category] → [Item] -tree.
+ **Returns** file metadata + items categorized; [Type] → [Category] → [Item] -tree.
  */
-pub fn verify_and_categorize_dta<R>(filename: &PathBuf, lines: Result<Lines<BufReader<R>>>, verbose: bool) -> HashMap<Context, ContextPayload>
-where R: Sized + Read
+pub fn verify_and_categorize_dta<R>(filename: &PathBuf, lines: Result<DtaReader<R>>, verbose: bool) -> DtaFile
+where R: BufRead
 {
     let lines = combine_lines(lines);
     if !lines.is_empty() {
@@ -31,6 +34,8 @@ where R: Sized + Read
         let mut curr_type: Option<Context> = None;
         let mut curr_category: String = String::from("");
         let mut unprocessed_items: HashMap<Context, ContextPayload> = HashMap::new();
+        let mut meta = meta_for(filename);
+        let mut pending_notes: Vec<String> = vec![];
 
         let rx_whitespace = Regex::new(r"^(\s|)*$").unwrap();
         // DTA regexes
@@ -58,8 +63,7 @@ where R: Sized + Read
         let mut genre: Lazy<Genre> = Lazy::new(Genre::new);
         let mut processing_genre = false;
 
-        for (file_line, line) in lines.iter().enumerate() {
-            let curr_line = file_line + 1;
+        for (file_line, DtaLine { number: curr_line, text: line }) in lines.iter().enumerate() {
             //
             // Detect file type. First line of file determines that.
             //
@@ -80,7 +84,7 @@ where R: Sized + Read
                 match file_line {
                     ..=1 => genre.name = line.to_string(),
                     2 => genre.title = line.to_string(),
-                    n => if let Some(x) = rx_genre_tl.captures(&line) {
+                    _ => if let Some(x) = rx_genre_tl.captures(&line) {
                         let (mut default, mut min, mut max) = match genre.tl {
                             TL::About { default, min, max } => (default, min, max),
                             TL::Exact(x) => (x,x,x)
@@ -90,7 +94,7 @@ where R: Sized + Read
                             "default" => default = tl,
                             "min" => min = tl,
                             "max" => max = tl,
-                            m => unreachable!("Errorneous TL mode: \"{m}\" on line {n}?!")
+                            m => unreachable!("Errorneous TL mode: \"{m}\" on line {curr_line}?!")
                         }
                         genre.tl = TL::About { default, min, max }
                     } else if let Some(x) = rx_genre_attr.captures(&line) {
@@ -98,7 +102,7 @@ where R: Sized + Read
                         match x.name("mode").unwrap().as_str() {
                             "attr" => genre.max_attr_default = Some(val),
                             "skill" => genre.max_skill_default = Some(val),
-                            m => unreachable!("Errorneous attr/skill mode: \"{m}\" on line {n}?!")
+                            m => unreachable!("Errorneous attr/skill mode: \"{m}\" on line {curr_line}?!")
                         }
                     } else if !line.is_empty() && !rx_whitespace.is_match(line) {
                         // anything that didn't match a regex is a filename/list of filenames (8.3 letter MS-DOS format).
@@ -114,24 +118,40 @@ where R: Sized + Read
             // Title?
             //
             if let Some(caps) = rx_title.captures(line.as_str()) {
-                if verbose {println!("   \"{}\"", caps.name("title").unwrap().as_str())}
+                let title = caps.name("title").unwrap().as_str().trim();
+                if verbose {println!("   \"{}\"", title)}
+                // Concatenated files might carry several titles - the first one wins.
+                if meta.title.is_none() {
+                    meta.title = title.to_string().into()
+                }
                 continue;
             }
             // Author?
             if let Some(caps) = rx_author.captures(line.as_str()) {
-                if verbose {println!("    \"{}\"", caps.name("author").unwrap().as_str())}
+                let author = caps.name("author").unwrap().as_str().trim();
+                if verbose {println!("    \"{}\"", author)}
+                if !author.is_empty() && !meta.authors.iter().any(|a| a.eq(author)) {
+                    meta.authors.push(author.to_string())
+                }
                 continue;
             }
 
             /*
-             We skip all empty (or all-whitespace) lines and lines which are
-             considered to be comments, e.g.:
+             Lines which are considered to be comments are collected as notes, e.g.:
                * a comment
                # another comment
+             and all empty (or all-whitespace) lines are skipped.
             */
             if line.starts_with("*")
             || line.starts_with("#")
-            || line.is_empty()
+            {
+                let note = line[1..].trim();
+                if !note.is_empty() {
+                    pending_notes.push(note.to_string())
+                }
+                continue;
+            }
+            if line.is_empty()
             || rx_whitespace.is_match(line.as_str())
             {
                 continue;
@@ -142,6 +162,10 @@ where R: Sized + Read
             //
             if let Some(caps) = rx_context_type.captures(line.as_str()) {
                 curr_category.clear();// Clear current category upon type change.
+                // Anything commented before the very first "type" is about the file itself.
+                if curr_type.is_none() {
+                    meta.notes.append(&mut pending_notes);
+                }
                 let typ = context_from_str(caps.get(1).unwrap().as_str());
                 if curr_type != Some(typ.clone()) {
                     curr_type = typ.clone().into();
@@ -169,6 +193,9 @@ where R: Sized + Read
                         }
                     }
                 }
+                if let Some(cat) = unprocessed_items.get_mut(&curr_type.clone().unwrap()).and_then(|typ| typ.items.get_mut(cat_name)) {
+                    cat.notes.append(&mut pending_notes);
+                }
 
                 if verbose {println!("C: {:?}", curr_category);}
                 continue;
@@ -201,25 +228,44 @@ where R: Sized + Read
                     typ.items.get_mut(curr_category.as_str()).and_then(|cat|{
                         let item_name = caps.name("name").unwrap().as_str().to_string();
                         if verbose {println!("› {item_name} → {}", caps.name("data").unwrap().as_str());}
-                        cat.items.insert(item_name.clone(), category_payload_from_triple((&typ.context, item_name.as_str(), caps.name("data").unwrap().as_str())))
+                        if !pending_notes.is_empty() {
+                            // Comments between the category and its first item are column headers and such.
+                            if cat.items.is_empty() {
+                                cat.notes.append(&mut pending_notes)
+                            } else {
+                                cat.item_notes.insert(item_name.clone(), std::mem::take(&mut pending_notes));
+                            }
+                        }
+                        let payload = category_payload_from_triple((&typ.context, item_name.as_str(), caps.name("data").unwrap().as_str()));
+                        match &payload {
+                            CategoryPayload::Equipment(e) if !e.spec().unparsed.is_empty() =>
+                                eprintln!("WARNING: unparsed spec(s) {:?} of \"{item_name}\" on line {} in {}", e.spec().unparsed, curr_line, filename.display()),
+                            CategoryPayload::Modifier(m) if !m.unparsed.is_empty() =>
+                                eprintln!("WARNING: unparsed effect(s) {:?} of \"{item_name}\" on line {} in {}", m.unparsed, curr_line, filename.display()),
+                            _ => ()
+                        }
+                        cat.items.insert(item_name.clone(), payload)
                     })
                 );
             } else {
-                panic!("No match?! {}", line.as_str())
+                panic!("No match?! {} on line {} in {}", line.as_str(), curr_line, filename.display())
             }
         }
 
         if processing_genre {
             unprocessed_items.insert(Context::Genre, ContextPayload { context: Context::Genre, items: {
                 let mut categorymap = HashMap::new();
-                let mut categorypayloadmap = HashMap::new();
-                categorypayloadmap.insert(Context::Genre.to_string(), CategoryPayload::Genre(genre.clone()));
-                categorymap.insert(Context::Genre.to_string(), Category { name: Context::Genre.to_string(), items: categorypayloadmap });
+                let mut category = Category::new(&Context::Genre.to_string());
+                category.items.insert(Context::Genre.to_string(), CategoryPayload::Genre(genre.clone()));
+                categorymap.insert(Context::Genre.to_string(), category);
                 categorymap
             } });
+        } else if curr_type.is_none() {
+            // Nothing but comments (and maybe a title)?
+            meta.notes.append(&mut pending_notes);
         }
         
-        unprocessed_items
+        DtaFile { meta, items: unprocessed_items }
     } else {
         panic!("Something gone wrong with {:?}", filename.display())
     }
@@ -227,9 +273,9 @@ where R: Sized + Read
 
 #[cfg(test)]
 mod parse_dta_tests {
-    use std::{collections::HashMap, io::{BufRead, BufReader, Cursor}, path::PathBuf};
+    use std::{collections::HashMap, io::Cursor, path::PathBuf};
 
-    use gurpschgen_lib::{context::{Context, ContextPayload}, damage::{Damage, DamageDelivery}, dta::{locate_dta::locate_dta, read_lines::read_lines}, equipment::{weapon::{ranged::{rof::RoF, shots::{Battery, Shots}, Ranged}, Weapon}, Equipment}, misc::{category::{Category, CategoryPayload}, tl::TL}};
+    use gurpschgen_lib::{context::{Context, ContextPayload}, damage::{Damage, DamageDelivery}, dta::{locate_dta::locate_dta, read_lines::{read_dta, DtaReader}}, equipment::{spec::Spec, weapon::{ranged::{rof::RoF, shots::{Battery, Shots}, Ranged}, Weapon}, Equipment}, misc::{category::{Category, CategoryPayload}, tl::TL}};
 
     use super::verify_and_categorize_dta;
     //use super::STEVE_JACKSONS_GEN_FORMAT_RX;
@@ -238,14 +284,49 @@ mod parse_dta_tests {
     fn parse_starts_makechar_format() {
         locate_dta(true);
         let filename = PathBuf::from("test.dta");
-        verify_and_categorize_dta(&filename, read_lines(&filename), true);
+        verify_and_categorize_dta(&filename, read_dta(&filename), true);
+    }
+
+    #[test]
+    fn parse_keeps_title_and_notes() {
+        locate_dta(false);
+        let filename = PathBuf::from("test.dta");
+        let dta = verify_and_categorize_dta(&filename, read_dta(&filename), false);
+        assert_eq!(Some("(test.dta) GURPS Test data".to_string()), dta.meta.title);
+        assert_eq!(Some("test.dta".to_string()), dta.meta.source);
+        assert!(dta.meta.converter.is_some());
+        let Some(pkgs) = dta.items.get(&Context::Package) else {panic!("No packages?!")};
+        let Some(cat) = pkgs.items.get("Race Packages") else {panic!("No race packages?!")};
+        assert_eq!(vec!["name; point-cost/$-cost; bonuses; gives; equipment gives".to_string()], cat.notes);
+        assert!(cat.item_notes.is_empty());
+    }
+
+    #[test]
+    fn parse_attaches_notes_to_items() {
+        let raw = "GURPS data file (this MUST be the first line!)
+* Just a test file.
+author Someone
+type quirk
+ category Quirkies
+  Quirked
+* This one is special.
+  Quirkier
+";
+        let br = DtaReader::new(Cursor::new(raw));
+        let filename = PathBuf::from("parse_attaches_notes_to_items");
+        let dta = verify_and_categorize_dta(&filename, Ok(br), false);
+        assert_eq!(vec!["Just a test file.".to_string()], dta.meta.notes);
+        assert_eq!(vec!["Someone".to_string()], dta.meta.authors);
+        let cat = dta.items.get(&Context::Quirk).unwrap().items.get("Quirkies").unwrap();
+        assert!(cat.notes.is_empty());
+        assert_eq!(Some(&vec!["This one is special.".to_string()]), cat.item_notes.get("Quirkier"));
     }
 
     #[test]
     fn parse_starts_xcg_format() {
         locate_dta(true);
         let filename = PathBuf::from("test2.dta");
-        verify_and_categorize_dta(&filename, read_lines(&filename), true);
+        verify_and_categorize_dta(&filename, read_dta(&filename), true);
     }
 
     #[test]
@@ -253,14 +334,14 @@ mod parse_dta_tests {
     fn parse_panic_with_unrecognized_file() {
         locate_dta(true);
         let filename = PathBuf::from("test3.dta");
-        verify_and_categorize_dta(&filename, read_lines(&filename), true);
+        verify_and_categorize_dta(&filename, read_dta(&filename), true);
     }
 
     #[test]
     fn parse_returned_hashmap_is_as_expected() {
         locate_dta(true);
         let filename = PathBuf::from("_x.dump");
-        let dump = verify_and_categorize_dta(&filename, read_lines(&filename), true);
+        let dump = verify_and_categorize_dta(&filename, read_dta(&filename), true);
         println!("{}", serde_json::to_string(&dump).unwrap());
     }
 
@@ -278,12 +359,10 @@ mod parse_dta_tests {
             st_req: None, tripod: false, cost: Some(125.75), weight: Some(2.25),
             skill: "Thing Weapon".to_string().into(), notes: Some("This is a note".to_string()),
             shots: Some(Shots::Battery(50, Battery::C)), mod_groups: vec!["Lazoring".to_string()],
-            rl_year: None, rl_country: None, tl: Some(8), lc: Some(0)
+            spec: Spec { tl: Some(8), lc: Some(0), ..Default::default() }.into()
         }))));
-        let cat = Category {
-            name: "Things".to_string(),
-            items: cat_items,
-        };
+        let mut cat = Category::new("Things");
+        cat.items = cat_items;
         items.insert("Things".to_string(), cat);
         let t = ContextPayload {
             context: Context::Equipment,
@@ -310,12 +389,11 @@ mod parse_dta_tests {
             40   Maximum skill value from which a skill can default
             basic.dta tl10basi.dta optbasic.dta humannat.dta psionics.dta martial.dta spacenav.dta tl10equi.dta tl9equip.dta tl8equip.dta tl7equip.dta aliens.dta 
         ";
-        let cursor = Cursor::new(raw);
-        let br = BufReader::new(cursor).lines();
+        let br = DtaReader::new(Cursor::new(raw));
         let mut filename = PathBuf::new();
         filename.set_file_name("parse_gen_works");
         let gmap = verify_and_categorize_dta(&filename, Ok(br), false);
-        if let Some(g) = gmap.get(&Context::Genre) {
+        if let Some(g) = gmap.items.get(&Context::Genre) {
             if let Some(i) = g.items.get("genre") {
                 if let Some(p) = i.items.get("genre") {
                     match p {
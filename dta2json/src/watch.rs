@@ -0,0 +1,227 @@
+use std::{collections::HashMap, env, fs, panic, path::{Path, PathBuf}, thread, time::{Duration, SystemTime}};
+
+use gurpschgen_lib::dta::{genre::Genre, read_lines::read_dta};
+
+use crate::verify_dta::verify_and_categorize_dta;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/**
+ Is `path` a DTA/GEN file (by extension, case-insensitive &ndash; DOS names tend to be ALL CAPS)?
+ */
+fn is_source(path: &Path) -> bool {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(e) => matches!(e.to_ascii_lowercase().as_str(), "dta" | "gen"),
+        None => false
+    }
+}
+
+/**
+ Where the JSON of `source` goes: same directory, same stem, `.json` extension.
+ */
+pub(crate) fn output_path(source: &Path) -> PathBuf {
+    source.with_extension("json")
+}
+
+/**
+ Snapshot modification times of all DTA/GEN files in `dir`.
+ */
+fn scan(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    let mut found = HashMap::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !is_source(&path) { continue; }
+            if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                found.insert(path, modified);
+            }
+        }
+    }
+    found
+}
+
+/**
+ Get sources which are new or modified in `curr` compared to `prev`.
+ */
+fn changed(prev: &HashMap<PathBuf, SystemTime>, curr: &HashMap<PathBuf, SystemTime>) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = curr.iter()
+        .filter(|(p, t)| prev.get(*p) != Some(*t))
+        .map(|(p, _)| p.clone())
+        .collect();
+    changed.sort();
+    changed
+}
+
+/**
+ Does `source` lack an up-to-date JSON counterpart?
+ */
+fn is_stale(source: &Path) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(source), modified(&output_path(source))) {
+        (Some(src), Some(out)) => src > out,
+        _ => true
+    }
+}
+
+/**
+ Dig the message out of a panic payload.
+ */
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown error".to_string()
+    }
+}
+
+/**
+ Convert a single DTA/GEN file into JSON (see [output_path]).
+
+ **Returns** either the path of the written JSON or whatever diagnostic the conversion died with.
+ */
+pub(crate) fn convert(source: &Path, verbose: bool) -> Result<PathBuf, String> {
+    let src = source.to_path_buf();
+    let dta = panic::catch_unwind(|| verify_and_categorize_dta(&src, read_dta(&src), verbose))
+        .map_err(panic_message)?;
+    let out = output_path(source);
+    let json = serde_json::to_string(&dta).map_err(|e| e.to_string())?;
+    fs::write(&out, json).map_err(|e| format!("{}: {e}", out.display()))?;
+    Ok(out)
+}
+
+/**
+ Find `.genre` files in `dir` which use any of the `converted` files.
+ */
+fn affected_genres(dir: &Path, converted: &[PathBuf]) -> Vec<PathBuf> {
+    let names: Vec<_> = converted.iter().filter_map(|c| c.file_name()).collect();
+    let mut genres = vec![];
+    let Ok(entries) = fs::read_dir(dir) else { return genres };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("genre") { continue; }
+        let Ok(json) = fs::read_to_string(&path) else { continue };
+        let Ok(genre) = serde_json::from_str::<Genre>(&json) else {
+            // A broken genre is affected by anything - let the validation tell what's wrong.
+            genres.push(path);
+            continue;
+        };
+        if genre.files.iter().any(|f| Path::new(f).file_name().is_some_and(|f| names.contains(&f))) {
+            genres.push(path);
+        }
+    }
+    genres.sort();
+    genres
+}
+
+/**
+ Validate a `.genre` by loading it (and all the files it refers to) with [Genre::load].
+ */
+fn validate_genre(genre: &Path) -> Result<(), String> {
+    let genre = genre.to_path_buf();
+    panic::catch_unwind(|| { Genre::load(&genre); }).map_err(panic_message)
+}
+
+/**
+ Convert the `sources` and report; optionally validate `.genre` files affected.
+ */
+fn convert_all(dir: &Path, sources: &[PathBuf], validate: bool, verbose: bool) {
+    let mut converted = vec![];
+    for source in sources {
+        let name = source.file_name().unwrap_or_default().to_string_lossy();
+        match convert(source, verbose) {
+            Ok(out) => {
+                println!("✓ {name} → {}", out.file_name().unwrap_or_default().to_string_lossy());
+                converted.push(out)
+            },
+            Err(e) => println!("✗ {name}: {e}")
+        }
+    }
+
+    if validate && !converted.is_empty() {
+        for genre in affected_genres(dir, &converted) {
+            let name = genre.file_name().unwrap_or_default().to_string_lossy();
+            match validate_genre(&genre) {
+                Ok(_) => println!("✓ {name} loads fine."),
+                Err(e) => println!("✗ {name}: {e}")
+            }
+        }
+    }
+}
+
+/**
+ Watch `dir` and (re)convert DTA/GEN files as they change. Never returns.
+
+ Upon start, files without an up-to-date JSON counterpart get converted.
+ */
+pub(crate) fn watch(dir: &Path, validate: bool, verbose: bool) -> ! {
+    // Genre::load() resolves its files relative to CWD.
+    env::set_current_dir(dir).unwrap_or_else(|e| panic!("FATAL: cannot watch {}: {e}", dir.display()));
+    let dir = PathBuf::from(".");
+    println!("Watching {} for DTA/GEN changes... (Ctrl+C to stop)", env::current_dir().unwrap().display());
+
+    let mut known = scan(&dir);
+    let mut stale: Vec<PathBuf> = known.keys().filter(|p| is_stale(p)).cloned().collect();
+    stale.sort();
+    convert_all(&dir, &stale, validate, verbose);
+
+    loop {
+        thread::sleep(POLL_INTERVAL);
+        let curr = scan(&dir);
+        let changed = changed(&known, &curr);
+        if !changed.is_empty() {
+            convert_all(&dir, &changed, validate, verbose);
+        }
+        known = curr;
+    }
+}
+
+#[cfg(test)]
+mod watch_tests {
+    use std::{collections::HashMap, fs, path::{Path, PathBuf}, time::{Duration, SystemTime}};
+
+    use super::{affected_genres, changed, convert, is_source, is_stale, output_path};
+
+    fn tmp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dta2json-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn sources_are_recognized() {
+        assert!(is_source(Path::new("BASIC.DTA")));
+        assert!(is_source(Path::new("space.gen")));
+        assert!(!is_source(Path::new("basic.json")));
+        assert_eq!(PathBuf::from("x/BASIC.json"), output_path(Path::new("x/BASIC.DTA")));
+    }
+
+    #[test]
+    fn changes_are_detected() {
+        let t0 = SystemTime::UNIX_EPOCH;
+        let t1 = t0 + Duration::from_secs(1);
+        let prev = HashMap::from([(PathBuf::from("a.dta"), t0), (PathBuf::from("b.dta"), t0)]);
+        let curr = HashMap::from([(PathBuf::from("a.dta"), t0), (PathBuf::from("b.dta"), t1), (PathBuf::from("c.gen"), t0)]);
+        assert_eq!(vec![PathBuf::from("b.dta"), PathBuf::from("c.gen")], changed(&prev, &curr));
+    }
+
+    #[test]
+    fn convert_and_affected_genres_work() {
+        let dir = tmp_dir("convert");
+        let src = dir.join("quirks.dta");
+        fs::write(&src, "GURPS data file (this MUST be the first line!)\ntype quirk\n category Quirkies\n  Quirked\n").unwrap();
+        assert!(is_stale(&src));
+        let out = convert(&src, false).unwrap();
+        assert!(out.exists());
+        assert!(!is_stale(&src));
+
+        fs::write(dir.join("test.genre"), r#"{"name":"T","title":"T","tl":{"Exact":3},"max_attr_default":null,"max_skill_default":null,"files":["quirks.json"]}"#).unwrap();
+        fs::write(dir.join("other.genre"), r#"{"name":"O","title":"O","tl":{"Exact":3},"max_attr_default":null,"max_skill_default":null,"files":["other.json"]}"#).unwrap();
+        assert_eq!(vec![dir.join("test.genre")], affected_genres(&dir, &[out]));
+
+        fs::write(&src, "foobarbaz\n").unwrap();
+        assert!(convert(&src, false).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
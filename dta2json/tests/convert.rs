@@ -0,0 +1,16 @@
+use std::process::Command;
+
+#[test]
+fn warnings_stay_out_of_the_json() {
+    let filename = std::env::temp_dir().join("dta2json-convert-test.dta");
+    std::fs::write(&filename, "GURPS data file (this MUST be the first line!)
+type equipment
+ category Weapons
+  AK-47;Cr/5+1, Acc+7, SS 12, 1/2D 500, Max 4000, RoF 10*, Shots 30, ST 10, Rcl -2, Folding stock; 300,10.5; Guns: Rifle
+").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_dta2json")).arg(&filename).output().unwrap();
+    std::fs::remove_file(&filename).ok();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("WARNING: unparsed spec(s) [\"Folding stock\"]"));
+    assert!(serde_json::from_slice::<serde_json::Value>(&output.stdout).is_ok());
+}
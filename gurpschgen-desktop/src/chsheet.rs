@@ -1,20 +1,29 @@
 use std::path::PathBuf;
 
 use dioxus::prelude::*;
-use gurpschgen_lib::dta::genre::Genre;
+use gurpschgen_lib::{config::Config, dta::genre::Genre};
 
+/**
+ Character sheet, for `genre` &ndash; and the `campaign` config at that path, if any.
+ */
 #[component]
-pub(crate) fn CharacterSheet(genre: String) -> Element {
+pub(crate) fn CharacterSheet(genre: String, campaign: String) -> Element {
     let mut val_st = use_signal(|| 10);
     let mut val_dx = use_signal(|| 10);
     let mut val_iq = use_signal(|| 10);
     let mut val_ht = use_signal(|| 10);
     let g = Genre::load(&PathBuf::from(genre.to_string()));
+    let config = if campaign.is_empty() { None } else { Config::load(&PathBuf::from(&campaign)).ok() };
 
     rsx! {
         div {
             "TODO: a character sheet, using " b{"{g.name}"} " as genre."
         }
+        if let Some(c) = &config {
+            div {
+                "Campaign: {c.edition:?}, TL{c.tl}, {c.points} points."
+            }
+        }
 
         div {
             id: "attr_st",
@@ -1,15 +1,20 @@
 use dioxus::prelude::*;
-use gurpschgen_lib::dta::genre::list_genre_files;
+use gurpschgen_lib::{config::{list_campaign_files, Config}, dta::genre::list_genre_files};
 
 use crate::routing::Route;
 
 /**
- Genre chooser element.
+ Genre chooser element. A campaign config, if picked, chooses the genre it points at.
  */
 #[component]
 pub(crate) fn ChooseGenre() -> Element {
     let mut genre: Signal<String> = use_signal(|| "".to_string());
+    let mut campaign: Signal<String> = use_signal(|| "".to_string());
     let genre_list = list_genre_files();
+    let campaigns: Vec<(String, Config)> = list_campaign_files().into_iter()
+        .filter_map(|f| Config::load(&f).ok().map(|c| (f.display().to_string(), c)))
+        .filter(|(_, c)| c.genre.is_some())
+        .collect();
 
     rsx! {
         div {
@@ -19,13 +24,27 @@ pub(crate) fn ChooseGenre() -> Element {
                 h1 { "Choose a genre below (current: {genre.to_string()})"}
             }
             for g in genre_list.into_iter() {
-                button { onclick: move |_| genre.set(g.clone().display().to_string()), "{g.display()}" }
+                button { onclick: move |_| {
+                    genre.set(g.clone().display().to_string());
+                    campaign.set("".to_string());
+                }, "{g.display()}" }
+            }
+        }
+        if !campaigns.is_empty() {
+            div {
+                h2 { "…or a campaign:" }
+                for (name, c) in campaigns.into_iter() {
+                    button { onclick: move |_| {
+                        genre.set(c.genre.clone().unwrap_or_default());
+                        campaign.set(name.clone());
+                    }, "{name}" }
+                }
             }
         }
         if !genre.to_string().is_empty() {
             div {
                 Link {
-                    to: Route::CharacterSheet { genre: genre.to_string() },
+                    to: Route::CharacterSheet { genre: genre.to_string(), campaign: campaign.to_string() },
                     button { "Button" }
                 }
             }
@@ -13,6 +13,6 @@ pub(crate) enum Route {
     Help { id: i32 },
     #[route("/genre")]
     ChooseGenre {},
-    #[route("/sheet/:genre")]
-    CharacterSheet { genre: String },
+    #[route("/sheet/:genre?:campaign")]
+    CharacterSheet { genre: String, campaign: String },
 }
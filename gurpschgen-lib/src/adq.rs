@@ -5,7 +5,7 @@ use crate::misc::{costly::Costly, leveled::Leveled, mod_grouped::ModGrouped, nam
 /**
  Container for advantages, disadvantages and quirks.
  */
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Adq {
     pub name: String,
     pub initial_cost: i32,
@@ -15,6 +15,12 @@ pub struct Adq {
     pub bonus_mods: Vec<String>,
     pub given: Vec<String>,
     pub mod_groups: Vec<String>,
+    /// Equipment granted (by packages) along with quantities.
+    #[serde(default)]
+    pub equipment_given: Vec<(String, usize)>,
+    /// Cash cost (of packages), on top of the point cost.
+    #[serde(default)]
+    pub cash_cost: f64,
 }
 
 impl Adq {
@@ -46,6 +52,22 @@ impl Adq {
         &self.given
     }
 
+    /**
+     Get names and quantities of equipment the [Adq] gives with it &ndash; usually relevant only for packages.
+
+     **Returns** a (possibly empty) vector of `(name, quantity)` pairs.
+     */
+    pub fn equipment_gives(&self) -> &Vec<(String, usize)> {
+        &self.equipment_given
+    }
+
+    /**
+     Get cash cost of the [Adq] &ndash; usually relevant only for packages, which may come with a price tag.
+     */
+    pub fn cash_cost(&self) -> f64 {
+        self.cash_cost
+    }
+
     /**
      Whatever these are&hellip; nobody knows.
      */
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{adq::Adq, attrib::AttributeType, secondary::PurchaseError, skill::Skill};
+
+/**
+ A single thing bought (or, with negative levels, bought off) with earned points.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Purchase {
+    /// Raise an attribute by `levels`.
+    Attribute { attrib: AttributeType, levels: i32 },
+    /// Raise [Skill::rank] of an already known skill.
+    Skill { name: String, ranks: i32 },
+    /// Learn a new skill, at whatever rank it comes with.
+    NewSkill(Skill),
+    /// Raise [Adq::level] of an already owned advantage (or buy off levels of a disadvantage).
+    Adq { name: String, levels: i32 },
+    /// Gain a new advantage (or disadvantage), at whatever level it comes with.
+    NewAdq(Adq),
+}
+
+/**
+ A journal entry &ndash; points awarded on some date, and what was bought with them.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Advancement {
+    /// Date of the award; free-form, though ISO 8601 sorts nicely.
+    pub date: String,
+    /// Points awarded.
+    pub awarded: i32,
+    pub purchases: Vec<Purchase>,
+    /// Points spent, as priced at the time of advancement.
+    #[serde(default)]
+    pub(crate) spent: f64,
+}
+
+/**
+ Reasons why an [Advancement] can't be applied.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdvancementError {
+    /// No skill/advantage of that name to raise.
+    NotFound(String),
+    /// Raise went out of bounds, etc.
+    Purchase(PurchaseError),
+    /// Purchases `cost` more than there's `unspent`.
+    NotEnoughPoints { cost: f64, unspent: f64 },
+}
+
+impl From<PurchaseError> for AdvancementError {
+    fn from(value: PurchaseError) -> Self {
+        Self::Purchase(value)
+    }
+}
+
+impl Advancement {
+    /**
+     Start a new journal entry of `awarded` points on `date`.
+     */
+    pub fn new(date: &str, awarded: i32) -> Self {
+        Self { date: date.to_string(), awarded, purchases: vec![], spent: 0.0 }
+    }
+
+    /**
+     Add a purchase to the entry.
+     */
+    pub fn with(mut self, purchase: Purchase) -> Self {
+        self.purchases.push(purchase);
+        self
+    }
+
+    /**
+     Get points spent by the entry.
+     */
+    pub fn spent(&self) -> f64 {
+        self.spent
+    }
+}
+
+#[cfg(test)]
+mod advancement_tests {
+    use crate::{attrib::{AttributeType, AttributeValued}, ch::Ch, fixtures, secondary::PurchaseError, skill::{DifficultyRating, Skill, SkillRoot, Stat}};
+
+    use super::{Advancement, AdvancementError, Purchase};
+
+    fn skill(name: &str, rank: usize) -> Skill {
+        Skill { rank, ..fixtures::skill(name, SkillRoot::P { stat: Stat::DX, diff: DifficultyRating::A }) }
+    }
+
+    #[test]
+    fn advancing_and_replay_work() {
+        let mut ch = Ch::new("Nameless");
+        let first = Advancement::new("2024-05-01", 25)
+            .with(Purchase::Attribute { attrib: AttributeType::DX, levels: 1 })
+            .with(Purchase::NewSkill(skill("Broadsword", 1)));
+        assert_eq!(Ok(21.0), ch.advance(first, 20));
+        assert_eq!(4.0, ch.unspent_points());
+
+        let greedy = Advancement::new("2024-05-15", 4)
+            .with(Purchase::Skill { name: "broadsword".into(), ranks: 2 });
+        assert_eq!(Err(AdvancementError::NotEnoughPoints { cost: 9.0, unspent: 8.0 }), ch.advance(greedy, 20));
+        assert_eq!(Err(AdvancementError::NotFound("Fencing".into())),
+            ch.advance(Advancement::new("2024-05-15", 5).with(Purchase::Skill { name: "Fencing".into(), ranks: 1 }), 20));
+        assert_eq!(Err(AdvancementError::Purchase(PurchaseError::OutOfBounds { min: 1, max: 20 })),
+            ch.advance(Advancement::new("2024-05-15", 5).with(Purchase::Attribute { attrib: AttributeType::DX, levels: 10 }), 20));
+        assert_eq!(1, ch.journal().len());
+
+        let second = Advancement::new("2024-05-15", 5)
+            .with(Purchase::Skill { name: "broadsword".into(), ranks: 1 });
+        assert_eq!(Ok(1.0), ch.advance(second, 20));
+        assert_eq!(8.0, ch.unspent_points());
+        assert_eq!(30, ch.awarded_points());
+        assert_eq!(2, ch.skills[0].rank);
+
+        let past = ch.replay(1);
+        assert_eq!(1, past.journal().len());
+        assert_eq!(1, past.skills[0].rank);
+        assert_eq!(11, past.dx.value());
+        assert_eq!(4.0, past.unspent_points());
+        let origin = ch.replay(0);
+        assert_eq!(10, origin.dx.value());
+        assert!(origin.skills.is_empty());
+        assert_eq!(0.0, origin.unspent_points());
+    }
+}
@@ -1,24 +1,31 @@
 use std::{cmp::max, collections::HashMap, ops::{Add, AddAssign, Sub, SubAssign}};
 
+use serde::{Deserialize, Serialize};
+
 use crate::{misc::costly::Costly, modifier::{Modifier, ModifierValue}};
 
-#[derive(Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
 pub enum AttributeType {
     DX, HT, IQ, ST,
 }
 
-#[derive(Debug, Clone)]
+impl AttributeType {
+    /// All of them, in the customary order.
+    pub const ALL: [AttributeType; 4] = [Self::ST, Self::DX, Self::IQ, Self::HT];
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AttributePayload {
     modifiers: HashMap<Modifier, Option<ModifierValue>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AttributeValue {
     base_val: i32,
     rel_val: i32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum Attribute {
     DX(AttributeValue, AttributePayload),
     HT(AttributeValue, AttributePayload),
@@ -213,6 +220,45 @@ impl AttributeValued for Attribute {
     }
 }
 
+impl Attribute {
+    /**
+     Get the attribute's [type][AttributeType].
+     */
+    pub fn attrib_type(&self) -> AttributeType {
+        match self {
+            Self::DX(..) => AttributeType::DX,
+            Self::HT(..) => AttributeType::HT,
+            Self::IQ(..) => AttributeType::IQ,
+            Self::ST(..) => AttributeType::ST,
+        }
+    }
+
+    /**
+     Set base value, e.g. as per racial template. See [AttributeValue::set_base_val].
+     */
+    pub fn set_base_val(&mut self, value: i32) -> &mut Self {
+        match self {
+            Self::DX(v, _) |
+            Self::HT(v, _) |
+            Self::IQ(v, _) |
+            Self::ST(v, _) => { v.set_base_val(value); v.set_rel_val(v.rel_val); }
+        };
+        self
+    }
+
+    /**
+     Get a [ModifierValue] of `modifier`, if set (and valued).
+     */
+    fn modifier_value(&self, modifier: &Modifier) -> Option<f64> {
+        match self {
+            Self::DX(_, p) |
+            Self::HT(_, p) |
+            Self::IQ(_, p) |
+            Self::ST(_, p) => p.modifiers.get(modifier)?.as_ref()?.get()
+        }
+    }
+}
+
 impl Costly for Attribute {
     fn cost(&self) -> f64 {
         let mut cost: f64;
@@ -233,6 +279,9 @@ impl Costly for Attribute {
                 }
             },
         }
+        if let Some(m) = self.modifier_value(&Modifier::CostMultiplier) {
+            cost *= m
+        }
         cost
     }
 }
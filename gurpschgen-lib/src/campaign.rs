@@ -0,0 +1,219 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ch::Ch, config::{BudgetError, Config}, dta::genre::Genre, inventory::Possession, misc::{costly::Costly, named::Named}};
+
+/**
+ A campaign &ndash; its config and a roster of characters, sharing a [Genre].
+ */
+#[derive(Debug, Clone)]
+pub struct Campaign<'a> {
+    genre: &'a Genre,
+    pub config: Config,
+    pub roster: Vec<Ch>,
+}
+
+/**
+ What's saved of a [Campaign]; the genre is loaded on its own.
+ */
+#[derive(Deserialize, Serialize)]
+struct CampaignFile {
+    config: Config,
+    #[serde(default)]
+    roster: Vec<Ch>,
+}
+
+/**
+ See if `items` (containers' contents included) have anything called `name`.
+ */
+fn has_equipment(items: &[Possession], name: &str) -> bool {
+    items.iter().any(|p| p.name().eq_ignore_ascii_case(name) || has_equipment(&p.contents, name))
+}
+
+impl<'a> Campaign<'a> {
+    /**
+     Start a new campaign, with an empty roster.
+     */
+    pub fn new(genre: &'a Genre, config: Config) -> Self {
+        Self { genre, config, roster: vec![] }
+    }
+
+    pub fn genre(&self) -> &Genre {
+        self.genre
+    }
+
+    /**
+     Add `ch` to the roster.
+     */
+    pub fn with(mut self, ch: Ch) -> Self {
+        self.roster.push(ch);
+        self
+    }
+
+    /**
+     Get roster member `name`.
+     */
+    pub fn get(&self, name: &str) -> Option<&Ch> {
+        self.roster.iter().find(|c| c.name == name)
+    }
+
+    /**
+     Get roster member `name` for editing.
+     */
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Ch> {
+        self.roster.iter_mut().find(|c| c.name == name)
+    }
+
+    /**
+     Remove roster member `name`.
+
+     **Returns** the removed `Ch`, if there was one.
+     */
+    pub fn remove(&mut self, name: &str) -> Option<Ch> {
+        let index = self.roster.iter().position(|c| c.name == name)?;
+        Some(self.roster.remove(index))
+    }
+
+    /**
+     Find who has (an advantage, package, skill or equipment called) `name`. Names are matched (ASCII) case-insensitively.
+     */
+    pub fn who_has(&self, name: &str) -> Vec<&Ch> {
+        self.roster.iter().filter(|c| {
+            c.advantages.iter().chain(c.packages.iter()).any(|a| a.name().eq_ignore_ascii_case(name))
+            || c.skills.iter().any(|s| s.name().eq_ignore_ascii_case(name))
+            || has_equipment(c.inventory.items(), name)
+        }).collect()
+    }
+
+    /**
+     Get total weight (lbs) the whole party carries.
+     */
+    pub fn total_weight(&self) -> f64 {
+        self.roster.iter().map(|c| c.carried_weight()).sum()
+    }
+
+    /**
+     Get average point value of the roster, if there's anyone in it.
+     */
+    pub fn average_points(&self) -> Option<f64> {
+        if self.roster.is_empty() {
+            return None;
+        }
+        Some(self.roster.iter().map(|c| c.cost()).sum::<f64>() / self.roster.len() as f64)
+    }
+
+    /**
+     Check everyone against the campaign's point limits.
+
+     **Returns** those who break them, and how.
+     */
+    pub fn over_budget(&self) -> Vec<(&Ch, BudgetError)> {
+        self.roster.iter()
+            .filter_map(|c| self.config.check_budget(c).err().map(|e| (c, e)))
+            .collect()
+    }
+
+    /**
+     Serialize the campaign (config and roster) as JSON.
+     */
+    pub fn to_json(&self) -> Result<String, String> {
+        let file = CampaignFile { config: self.config.clone(), roster: self.roster.clone() };
+        serde_json::to_string_pretty(&file).map_err(|e| e.to_string())
+    }
+
+    /**
+     Parse a campaign from JSON, to be played in `genre`.
+     */
+    pub fn from_json(json: &str, genre: &'a Genre) -> Result<Self, String> {
+        let file: CampaignFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        Ok(Self { genre, config: file.config, roster: file.roster })
+    }
+
+    /**
+     Save the whole campaign as one (JSON) file.
+     */
+    pub fn save(&self, filename: &Path) -> Result<(), String> {
+        std::fs::write(filename, self.to_json()?)
+            .map_err(|e| format!("{}: {e}", filename.display()))
+    }
+
+    /**
+     Load a campaign saved by [Campaign::save], to be played in `genre`.
+     */
+    pub fn load(filename: &Path, genre: &'a Genre) -> Result<Self, String> {
+        let content = std::fs::read_to_string(filename)
+            .map_err(|e| format!("{}: {e}", filename.display()))?;
+        Self::from_json(&content, genre)
+            .map_err(|e| format!("{}: {e}", filename.display()))
+    }
+}
+
+#[cfg(test)]
+mod campaign_tests {
+    use crate::{adq::Adq, attrib::AttributeValued, ch::Ch, config::{BudgetError, Config}, dta::genre::Genre, edition::GurpsEd, equipment::Equipment, fixtures::{adq, item, skill}, gender::Gender, skill::{DifficultyRating, Skill, SkillRoot, Stat}};
+
+    use super::Campaign;
+
+    fn rope() -> Equipment {
+        item("Rope", 10.0, 5.0)
+    }
+
+    fn broadsword() -> Skill {
+        Skill { rank: 2, ..skill("Broadsword", SkillRoot::P { stat: Stat::DX, diff: DifficultyRating::A }) }
+    }
+
+    fn party(genre: &Genre) -> Campaign<'_> {
+        let mut config = Config::default_tl3(GurpsEd::Ed3);
+        config.points = 30;
+        let mut fighter = Ch::new("Fighter");
+        fighter.st += 2;
+        fighter.skills.push(broadsword());
+        fighter.inventory.add(rope(), 2);
+        fighter.set_gender(Some(Gender::Female), &config);
+        let mut thief = Ch::new("Thief");
+        thief.dx += 2;
+        thief.advantages.push(Adq { level: 1, ..adq("Alertness", 5) });
+        Campaign::new(genre, config).with(fighter).with(thief)
+    }
+
+    #[test]
+    fn roster_queries_work() {
+        let genre = Genre::new();
+        let mut campaign = party(&genre);
+        assert_eq!(vec!["Fighter"], campaign.who_has("broadsword").iter().map(|c| c.name.as_str()).collect::<Vec<_>>());
+        assert_eq!(vec!["Fighter"], campaign.who_has("Rope").iter().map(|c| c.name.as_str()).collect::<Vec<_>>());
+        assert_eq!(vec!["Thief"], campaign.who_has("Alertness").iter().map(|c| c.name.as_str()).collect::<Vec<_>>());
+        assert!(campaign.who_has("Fencing").is_empty());
+        assert_eq!(10.0, campaign.total_weight());
+        // Fighter: 20 (ST) + 2 (Broadsword) - 5 (Female); Thief: 40 (DX) + 5 (Alertness).
+        assert_eq!(Some(31.0), campaign.average_points());
+        let over = campaign.over_budget();
+        assert_eq!(1, over.len());
+        assert_eq!(("Thief", BudgetError::OverBudget(15.0)), (over[0].0.name.as_str(), over[0].1.clone()));
+
+        assert!(campaign.remove("Thief").is_some());
+        assert!(campaign.get("Thief").is_none());
+        campaign.get_mut("Fighter").unwrap().ht += 1;
+        assert_eq!(Some(27.0), campaign.average_points());
+        assert_eq!(None, Campaign::new(&genre, Config::default_tl8(GurpsEd::Ed4)).average_points());
+    }
+
+    #[test]
+    fn save_and_load_work() {
+        let genre = Genre::new();
+        let campaign = party(&genre);
+        let filename = std::env::temp_dir().join("gurpschgen-campaign-test.json");
+        campaign.save(&filename).unwrap();
+        let loaded = Campaign::load(&filename, &genre).unwrap();
+        std::fs::remove_file(&filename).ok();
+        assert_eq!(campaign.config, loaded.config);
+        assert_eq!(2, loaded.roster.len());
+        let fighter = loaded.get("Fighter").unwrap();
+        assert_eq!(12, fighter.st.value());
+        assert_eq!(Some(&Gender::Female), fighter.gender());
+        assert_eq!(2, fighter.inventory.count("Rope"));
+        assert_eq!(campaign.average_points(), loaded.average_points());
+        assert!(Campaign::from_json("{}", &genre).is_err());
+    }
+}
@@ -1,11 +1,46 @@
-use crate::{attrib::{Attribute, AttributeType, AttributeValued}, gender::Gender, misc::costly::Costly};
+pub mod diff;
+pub mod statblock;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{adq::Adq, advancement::{Advancement, AdvancementError, Purchase}, attrib::{Attribute, AttributeType, AttributeValued}, config::Config, damage::{st_damage, Damage, DamageDelivery, DamageResistance, PassiveDefense}, dta::genre::Genre, edition::GurpsEd, encumbrance::{self, Encumbrance}, equipment::{weapon::{melee::{parry::Parry, reach::Reach, Melee}, Weapon}, Equipment}, gender::Gender, hit_location::{HitLocation, HIT_LOCATIONS}, inventory::Inventory, misc::{costly::Costly, damaged::Damaged, leveled::Leveled, named::Named, skilled::Skilled, st_req::STRequired}, modifier::{race::RacialTemplate, Modifier}, secondary::{PurchaseError, Secondary}, skill::Skill};
+
+/**
+ Armor protection at a hit location.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocationDefense {
+    pub location: &'static HitLocation,
+    pub dr: DamageResistance,
+    pub pd: PassiveDefense,
+}
+
+/**
+ Weapon damage as dealt by a particular `Ch`.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeaponDamage {
+    pub damage: Vec<Damage>,
+    /// Skill penalty due to lack of ST, if any.
+    pub skill_penalty: i32,
+}
 
 /**
  PC/NPC container.
  */
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Ch {
     pub name: String,
-    pub gender: Option<Gender>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gender: Option<Gender>,
+    /// Points gained (or spent) by gender, as per campaign at the time.
+    #[serde(default)]
+    gender_points: i32,
+    /// Racial template, if other than plain human.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    race: Option<RacialTemplate>,
     pub st: Attribute,
     pub dx: Attribute,
     pub iq: Attribute,
@@ -16,6 +51,18 @@ pub struct Ch {
     extra_fp: i32,
     extra_speed: i32,
     extra_move: i32,
+    /// Points spent on (or gained from) `extra_*`, per stat, priced as per edition at the time.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    extra_points: HashMap<Secondary, f64>,
+    pub advantages: Vec<Adq>,
+    pub packages: Vec<Adq>,
+    pub skills: Vec<Skill>,
+    /// Advancement journal, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    journal: Vec<Advancement>,
+    pub inventory: Inventory,
+    /// Cash at hand; starting wealth to begin with.
+    pub cash: f64,
 }
 
 impl Ch {
@@ -30,41 +77,286 @@ impl Ch {
             iq: Attribute::default(AttributeType::IQ),
             st: Attribute::default(AttributeType::ST),
             gender: None,// will be chosen later.
+            gender_points: 0,
+            race: None,
             extra_hp: 0,
             extra_will: 0,
             extra_per: 0,
             extra_fp: 0,
             extra_speed: 0,
             extra_move: 0,
+            extra_points: HashMap::new(),
+            advantages: vec![],
+            packages: vec![],
+            skills: vec![],
+            journal: vec![],
+            inventory: Inventory::new(),
+            cash: 0.0,
+        }
+    }
+
+    /**
+     Apply a `package` to `Ch`, paying its cash cost and receiving whatever equipment it grants into inventory.
+
+     **Params**
+     * `package` - some package, usually from `genre`'s [packages][crate::context::Context::Package].
+     * `genre` - genre to resolve equipment names against.
+
+     **Returns** names of granted equipment which `genre` doesn't know of (and thus weren't received).
+     */
+    pub fn apply_package(&mut self, package: &Adq, genre: &Genre) -> Vec<String> {
+        let mut unknown = vec![];
+        self.cash -= package.cash_cost();
+        // Zero quantity (`@0`) merely lists the equipment; there's nothing to receive.
+        for (name, quantity) in package.equipment_gives().iter().filter(|(_, q)| *q > 0) {
+            match genre.find_equipment(name) {
+                Some(e) => { self.inventory.add(e.clone(), *quantity); },
+                None => unknown.push(name.clone())
+            }
         }
+        let mut package = package.clone();
+        package.level = 1;
+        self.packages.push(package);
+        unknown
+    }
+
+    /**
+     Get `Ch`'s gender, if chosen.
+     */
+    pub fn gender(&self) -> Option<&Gender> {
+        self.gender.as_ref()
+    }
+
+    /**
+     Set (or with `None`, clear) `Ch`'s gender, applying whatever point value `config` gives it.
+     */
+    pub fn set_gender(&mut self, gender: Option<Gender>, config: &Config) {
+        self.gender_points = gender.as_ref().map_or(0, |g| config.gender_points(g));
+        self.gender = gender;
     }
 
     /**
-     Get `Ch`'s **h**it **p**oints (HP).
+     Get `Ch`'s racial template, if any.
      */
-    pub fn hp(&self) -> i32 {
-        self.st.value() + self.extra_hp
+    pub fn race(&self) -> Option<&RacialTemplate> {
+        self.race.as_ref()
     }
 
     /**
-     Get `Ch`'s **w**ill**p**ower (WP).
+     Set (or with `None`, clear) `Ch`'s racial template.
+
+     Base attributes become 10 + racial bonus and the template's [Modifier]s replace any previous ones.
      */
-    pub fn wp(&self) -> i32 {
-        self.iq.value() + self.extra_will
+    pub fn set_race(&mut self, race: Option<RacialTemplate>) {
+        for attrib in AttributeType::ALL {
+            let a = self.attribute_mut(attrib);
+            a.unset_modifier(Modifier::Size)
+             .unset_modifier(Modifier::NoFineManipulators)
+             .unset_modifier(Modifier::CostMultiplier);
+            a.set_base_val(10 + race.as_ref().map_or(0, |r| r.bonus(attrib)));
+            if let Some(r) = &race {
+                for m in r.attribute_modifiers(attrib) {
+                    a.set_modifier(m);
+                }
+            }
+        }
+        self.race = race;
     }
 
     /**
-     Get `Ch`'s ***per**ception (Per).
+     Get `Ch`'s Size Modifier (SM); zero unless the race says otherwise.
      */
-    pub fn per(&self) -> i32 {
-        self.iq.value() + self.extra_per
+    pub fn size_modifier(&self) -> i32 {
+        self.race.as_ref().map_or(0, |r| r.sm)
     }
 
     /**
-     Get `Ch`'s **f**atigue **p**oints (FP).
+     Get the [Attribute] of given `attrib` type.
      */
-    pub fn fp(&self) -> i32 {
-        self.ht.value() + self.extra_fp
+    pub fn attribute(&self, attrib: AttributeType) -> &Attribute {
+        match attrib {
+            AttributeType::DX => &self.dx,
+            AttributeType::HT => &self.ht,
+            AttributeType::IQ => &self.iq,
+            AttributeType::ST => &self.st,
+        }
+    }
+
+    fn attribute_mut(&mut self, attrib: AttributeType) -> &mut Attribute {
+        match attrib {
+            AttributeType::DX => &mut self.dx,
+            AttributeType::HT => &mut self.ht,
+            AttributeType::IQ => &mut self.iq,
+            AttributeType::ST => &mut self.st,
+        }
+    }
+
+    /**
+     Raise (or lower, if negative) `attrib` by `levels`, within racial limits &ndash; or within 1 and `genre_max` for humans.
+
+     **Returns** the resulting attribute value.
+     */
+    pub fn raise_attribute(&mut self, attrib: AttributeType, levels: i32, genre_max: i32) -> Result<i32, PurchaseError> {
+        let (min, max) = match &self.race {
+            Some(r) => r.bounds(attrib, genre_max),
+            None => (1, genre_max)
+        };
+        let a = self.attribute_mut(attrib);
+        let value = a.value() + levels;
+        if value < min || value > max {
+            return Err(PurchaseError::OutOfBounds { min, max });
+        }
+        *a += levels;
+        Ok(value)
+    }
+
+    /**
+     Get `weapon`'s reach as wielded by `Ch`, i.e. scaled by SM.
+     */
+    pub fn reach(&self, weapon: &Melee) -> Vec<Reach> {
+        match &self.race {
+            Some(r) => weapon.reach.iter().map(|x| r.scale_reach(*x)).collect(),
+            None => weapon.reach.clone()
+        }
+    }
+
+    /**
+     Get basic thrust damage.
+     */
+    pub fn thrust(&self, edition: &GurpsEd) -> DamageDelivery {
+        st_damage::thrust(self.st.value(), 0, edition)
+    }
+
+    /**
+     Get basic swing damage.
+     */
+    pub fn swing(&self, edition: &GurpsEd) -> DamageDelivery {
+        st_damage::swing(self.st.value(), 0, edition)
+    }
+
+    /**
+     Resolve ST-based `damage` (sw/thr) into dice. Other sorts of damage are returned as-is.
+     */
+    pub fn resolve_damage(&self, damage: &Damage, edition: &GurpsEd) -> Damage {
+        let delivery = match damage.delivery() {
+            DamageDelivery::Sw(adds) => st_damage::swing(self.st.value(), *adds, edition),
+            DamageDelivery::Thr(adds) => st_damage::thrust(self.st.value(), *adds, edition),
+            x => x.clone()
+        };
+        Damage::new(damage.damage_type(), delivery)
+    }
+
+    /**
+     Resolve `weapon`'s damage for `Ch`, and see if `Ch` is strong enough to wield it properly.
+
+     Each point of ST below the weapon's minimum is -1 to skill.
+     */
+    pub fn weapon_damage<W>(&self, weapon: &W, edition: &GurpsEd) -> WeaponDamage
+    where W: Damaged + STRequired
+    {
+        WeaponDamage {
+            damage: weapon.damage().iter().map(|d| self.resolve_damage(d, edition)).collect(),
+            skill_penalty: match weapon.st_req() {
+                Some(req) => (self.st.value() - req).min(0),
+                None => 0
+            },
+        }
+    }
+
+    /**
+     Get Parry with each melee weapon in inventory.
+
+     `skill_level` gives `Ch`'s level in the weapon's skill (or in the weapon itself, if it names no skill);
+     weapons `Ch` has no level in are left out. Lack of ST, weapon quality and advantages count, as does
+     encumbrance for fencing weapons in 4e.
+
+     **Returns** weapon name &amp; Parry pairs; `None` for weapons which can't parry.
+     */
+    pub fn parries<F>(&self, skill_level: F, config: &Config) -> Vec<(&str, Option<i32>)>
+    where F: Fn(&str) -> Option<i32>
+    {
+        let edition = &config.edition;
+        let bonus = self.defense_bonus("Enhanced Parry");
+        let fencing_penalty = match edition {
+            GurpsEd::Ed3 => 0,
+            GurpsEd::Ed4 => self.encumbrance(config).dodge_penalty(),
+        };
+        self.inventory.items().iter()
+            .filter_map(|p| match &p.equipment {
+                Equipment::Weapon(Weapon::Melee(m)) => {
+                    let level = skill_level(m.skill().unwrap_or(m.name()))?
+                        + self.weapon_damage(m, edition).skill_penalty
+                        + p.skill_modifier();
+                    let penalty = if let Parry::Fencing(_) = m.parry { fencing_penalty } else { 0 };
+                    Some((m.name(), m.parry.value(level, edition).map(|x| x + bonus + penalty)))
+                },
+                _ => None
+            })
+            .collect()
+    }
+
+    /**
+     Compute DR/PD per hit location from all worn armor &ndash; DR of layers stacks, PD doesn't.
+
+     **Returns** a location chart in hit location table order.
+     */
+    pub fn location_defenses(&self) -> Vec<LocationDefense> {
+        let armor: Vec<_> = self.inventory.worn()
+            .filter_map(|p| match &p.equipment {
+                Equipment::Armor(a) => Some((a, p.dr(), p.pd())),
+                _ => None
+            })
+            .collect();
+        HIT_LOCATIONS.iter().map(|location| {
+            let layers: Vec<_> = armor.iter().filter(|(a, _, _)| location.rolls.clone().any(|r| a.covers(r))).collect();
+            LocationDefense {
+                location,
+                dr: layers.iter().filter_map(|(_, dr, _)| dr.as_ref()).sum(),
+                pd: layers.iter().filter_map(|(_, _, pd)| pd.as_ref()).sum(),
+            }
+        }).collect()
+    }
+
+    /**
+     Get `Ch`'s **h**it **p**oints (HP) &ndash; based on ST in 4e, HT in 3e.
+     */
+    pub fn hp(&self, config: &Config) -> i32 {
+        let base = match config.edition {
+            GurpsEd::Ed3 => self.ht.value(),
+            GurpsEd::Ed4 => self.st.value(),
+        };
+        base + self.extra_hp
+    }
+
+    /**
+     Get `Ch`'s **w**ill**p**ower (WP). Plain IQ in 3e.
+     */
+    pub fn wp(&self, config: &Config) -> i32 {
+        match config.edition {
+            GurpsEd::Ed3 => self.iq.value(),
+            GurpsEd::Ed4 => self.iq.value() + self.extra_will,
+        }
+    }
+
+    /**
+     Get `Ch`'s ***per**ception (Per). Plain IQ in 3e.
+     */
+    pub fn per(&self, config: &Config) -> i32 {
+        match config.edition {
+            GurpsEd::Ed3 => self.iq.value(),
+            GurpsEd::Ed4 => self.iq.value() + self.extra_per,
+        }
+    }
+
+    /**
+     Get `Ch`'s **f**atigue **p**oints (FP) &ndash; based on HT in 4e, ST in 3e.
+     */
+    pub fn fp(&self, config: &Config) -> i32 {
+        let base = match config.edition {
+            GurpsEd::Ed3 => self.st.value(),
+            GurpsEd::Ed4 => self.ht.value(),
+        };
+        base + self.extra_fp
     }
 
     /**
@@ -75,33 +367,336 @@ impl Ch {
     }
 
     /**
-     Get `Ch`'s basic **move** score (yd/s).
+     Get `Ch`'s basic **move** score (yd/s), unencumbered.
      */
-    // 'move' is a reserved word, so...: mov() instead.
-    pub fn mov(&self) -> i32 {
+    pub fn basic_move(&self) -> i32 {
         (self.speed() + self.extra_move as f64).trunc() as i32
     }
+
+    /**
+     Get `Ch`'s **move** score (yd/s), encumbrance applied.
+     */
+    // 'move' is a reserved word, so...: mov() instead.
+    pub fn mov(&self, config: &Config) -> i32 {
+        self.encumbrance(config).apply_to_move(self.basic_move(), &config.edition)
+    }
+
+    /**
+     Get how many bought extra levels of `stat` `Ch` has.
+     */
+    pub fn extra(&self, stat: Secondary) -> i32 {
+        match stat {
+            Secondary::HP => self.extra_hp,
+            Secondary::Will => self.extra_will,
+            Secondary::Per => self.extra_per,
+            Secondary::FP => self.extra_fp,
+            Secondary::Speed => self.extra_speed,
+            Secondary::Move => self.extra_move,
+        }
+    }
+
+    /**
+     Get the allowed range of extra levels of `stat`.
+
+     HP and FP may differ ±30% from what they're based on, Will and Per have to stay within 4-20,
+     Basic Speed within ±2.00 and (4e) Basic Move within ±3.
+     */
+    pub fn extra_bounds(&self, stat: Secondary, config: &Config) -> (i32, i32) {
+        let spread = |base: i32| (-(base * 3 / 10), base * 3 / 10);
+        match stat {
+            Secondary::HP => spread(self.hp(config) - self.extra_hp),
+            Secondary::FP => spread(self.fp(config) - self.extra_fp),
+            Secondary::Will |
+            Secondary::Per => (4 - self.iq.value(), 20 - self.iq.value()),
+            Secondary::Speed => (-8, 8),
+            Secondary::Move => (-3, 3),
+        }
+    }
+
+    /**
+     Buy `levels` (or sell, if negative) of `stat`, priced per `config`'s edition.
+
+     **Returns** the resulting extra levels.
+     */
+    pub fn buy_extra(&mut self, stat: Secondary, levels: i32, config: &Config) -> Result<i32, PurchaseError> {
+        let mut price = stat.price(&config.edition).ok_or(PurchaseError::NotInEdition)?;
+        if let (Secondary::HP, Some(r)) = (stat, &self.race) {
+            price *= r.hp_cost_multiplier(&config.edition);
+        }
+        let step = stat.step(&config.edition);
+        if levels % step != 0 {
+            return Err(PurchaseError::Step(step));
+        }
+        let (min, max) = self.extra_bounds(stat, config);
+        let extra = self.extra(stat) + levels;
+        if extra < min || extra > max {
+            return Err(PurchaseError::OutOfBounds { min, max });
+        }
+        match stat {
+            Secondary::HP => self.extra_hp = extra,
+            Secondary::Will => self.extra_will = extra,
+            Secondary::Per => self.extra_per = extra,
+            Secondary::FP => self.extra_fp = extra,
+            Secondary::Speed => self.extra_speed = extra,
+            Secondary::Move => self.extra_move = extra,
+        }
+        *self.extra_points.entry(stat).or_default() += price * levels as f64;
+        Ok(extra)
+    }
+
+    /**
+     Get level of advantage `name`, zero if `Ch` doesn't have it.
+     */
+    pub fn advantage_level(&self, name: &str) -> usize {
+        self.advantages.iter()
+            .find(|a| a.name().eq_ignore_ascii_case(name))
+            .map_or(0, |a| a.level())
+    }
+
+    /**
+     Get bonus to an active defense from advantages: Combat Reflexes and the relevant `enhanced` one.
+     */
+    fn defense_bonus(&self, enhanced: &str) -> i32 {
+        (self.advantage_level("Combat Reflexes").min(1) + self.advantage_level(enhanced)) as i32
+    }
+
+    /**
+     Get `Ch`'s Dodge &ndash; encumbered Move in 3e, Basic Speed + 3 less encumbrance in 4e.
+     */
+    pub fn dodge(&self, config: &Config) -> i32 {
+        let base = match config.edition {
+            GurpsEd::Ed3 => self.mov(config),
+            GurpsEd::Ed4 => self.speed().trunc() as i32 + 3 + self.encumbrance(config).dodge_penalty(),
+        };
+        base + self.defense_bonus("Enhanced Dodge")
+    }
+
+    /**
+     Get `Ch`'s Block with a shield at `shield_skill`.
+     */
+    pub fn block(&self, shield_skill: i32, config: &Config) -> i32 {
+        let base = match config.edition {
+            GurpsEd::Ed3 => shield_skill / 2,
+            GurpsEd::Ed4 => 3 + shield_skill / 2,
+        };
+        base + self.defense_bonus("Enhanced Block")
+    }
+
+    /**
+     Get `Ch`'s Basic Lift (lbs).
+     */
+    pub fn basic_lift(&self, config: &Config) -> f64 {
+        encumbrance::basic_lift(self.st.value(), &config.edition)
+    }
+
+    /**
+     Get total weight (lbs) `Ch` carries, worn stuff included.
+     */
+    pub fn carried_weight(&self) -> f64 {
+        self.inventory.total_weight()
+    }
+
+    /**
+     Get `Ch`'s current encumbrance level. See also [Encumbrance::dodge_penalty].
+     */
+    pub fn encumbrance(&self, config: &Config) -> Encumbrance {
+        Encumbrance::from_weight(self.carried_weight(), self.basic_lift(config))
+    }
+}
+
+impl Ch {
+    /**
+     Get the cost ledger &ndash; what points went (or came from) where.
+
+     **Returns** `(entry, points)` pairs; attributes always, everything else only if present.
+     */
+    pub fn ledger(&self) -> Vec<(String, f64)> {
+        let mut ledger = vec![
+            ("ST".to_string(), self.st.cost()),
+            ("DX".to_string(), self.dx.cost()),
+            ("IQ".to_string(), self.iq.cost()),
+            ("HT".to_string(), self.ht.cost()),
+        ];
+        let extra_points: f64 = self.extra_points.values().sum();
+        if extra_points != 0.0 {
+            ledger.push(("Secondary characteristics".to_string(), extra_points));
+        }
+        if let Some(g) = self.gender.as_ref().filter(|_| self.gender_points != 0) {
+            ledger.push((format!("Gender: {g}"), self.gender_points as f64));
+        }
+        ledger.extend(self.advantages.iter().map(|a| (a.name().to_string(), a.cost())));
+        ledger.extend(self.packages.iter().map(|p| (p.name().to_string(), p.cost())));
+        ledger.extend(self.skills.iter().map(|s| (s.name().to_string(), s.cost())));
+        ledger
+    }
+
+    /**
+     Get points gained from disadvantages and other negative ledger entries (as a negative number, or zero).
+     */
+    pub fn disadvantage_points(&self) -> f64 {
+        self.ledger().iter().map(|(_, c)| *c).filter(|c| *c < 0.0).sum()
+    }
+}
+
+impl Ch {
+    /**
+     Get the advancement journal, oldest entry first.
+     */
+    pub fn journal(&self) -> &Vec<Advancement> {
+        &self.journal
+    }
+
+    /**
+     Get total points awarded through advancement.
+     */
+    pub fn awarded_points(&self) -> i32 {
+        self.journal.iter().map(|a| a.awarded).sum()
+    }
+
+    /**
+     Get awarded points not (yet) spent on anything.
+     */
+    pub fn unspent_points(&self) -> f64 {
+        self.awarded_points() as f64 - self.journal.iter().map(|a| a.spent()).sum::<f64>()
+    }
+
+    /**
+     Apply an `advancement` &ndash; all of its purchases, or (on error) none of them.
+
+     Attribute raises are bound by racial limits, or by `genre_max`.
+
+     **Returns** points spent.
+     */
+    pub fn advance(&mut self, mut advancement: Advancement, genre_max: i32) -> Result<f64, AdvancementError> {
+        let mut next = self.clone();
+        for p in &advancement.purchases {
+            next.apply_purchase(p, genre_max)?;
+        }
+        let cost = next.cost() - self.cost();
+        let unspent = self.unspent_points() + advancement.awarded as f64;
+        if cost > unspent {
+            return Err(AdvancementError::NotEnoughPoints { cost, unspent });
+        }
+        advancement.spent = cost;
+        next.journal.push(advancement);
+        *self = next;
+        Ok(cost)
+    }
+
+    /**
+     Replay `Ch` up to a past state &ndash; as it was after the first `entries` journal entries.
+     */
+    pub fn replay(&self, entries: usize) -> Ch {
+        let mut past = self.clone();
+        while past.journal.len() > entries {
+            let Some(advancement) = past.journal.pop() else { break };
+            for p in advancement.purchases.iter().rev() {
+                past.undo_purchase(p);
+            }
+        }
+        past
+    }
+
+    fn apply_purchase(&mut self, purchase: &Purchase, genre_max: i32) -> Result<(), AdvancementError> {
+        match purchase {
+            Purchase::Attribute { attrib, levels } => { self.raise_attribute(*attrib, *levels, genre_max)?; },
+            Purchase::Skill { name, ranks } => {
+                let skill = self.skills.iter_mut()
+                    .find(|s| s.name().eq_ignore_ascii_case(name))
+                    .ok_or(AdvancementError::NotFound(name.clone()))?;
+                let rank = skill.rank as i32 + ranks;
+                if rank < 0 {
+                    return Err(PurchaseError::OutOfBounds { min: 0, max: i32::MAX }.into());
+                }
+                skill.rank = rank as usize;
+            },
+            Purchase::Adq { name, levels } => {
+                let adq = self.advantages.iter_mut()
+                    .find(|a| a.name().eq_ignore_ascii_case(name))
+                    .ok_or(AdvancementError::NotFound(name.clone()))?;
+                let level = adq.level as i32 + levels;
+                let max = if adq.max_level > 0 { adq.max_level as i32 } else { i32::MAX };
+                if level < 0 || level > max {
+                    return Err(PurchaseError::OutOfBounds { min: 0, max }.into());
+                }
+                adq.level = level as usize;
+            },
+            Purchase::NewSkill(skill) => self.skills.push(skill.clone()),
+            Purchase::NewAdq(adq) => self.advantages.push(adq.clone()),
+        }
+        Ok(())
+    }
+
+    fn undo_purchase(&mut self, purchase: &Purchase) {
+        match purchase {
+            Purchase::Attribute { attrib, levels } => *self.attribute_mut(*attrib) -= *levels,
+            Purchase::Skill { name, ranks } => if let Some(s) = self.skills.iter_mut().find(|s| s.name().eq_ignore_ascii_case(name)) {
+                s.rank = (s.rank as i32 - ranks).max(0) as usize
+            },
+            Purchase::Adq { name, levels } => if let Some(a) = self.advantages.iter_mut().find(|a| a.name().eq_ignore_ascii_case(name)) {
+                a.level = (a.level as i32 - levels).max(0) as usize
+            },
+            Purchase::NewSkill(skill) => if let Some(i) = self.skills.iter().rposition(|s| s.name == skill.name) {
+                self.skills.remove(i);
+            },
+            Purchase::NewAdq(adq) => if let Some(i) = self.advantages.iter().rposition(|a| a.name == adq.name) {
+                self.advantages.remove(i);
+            },
+        }
+    }
 }
 
 impl Costly for Ch {
     fn cost(&self) -> f64 {
-          self.dx.cost()
-        + self.ht.cost()
-        + self.iq.cost()
-        + self.st.cost()
-        + 2.0 * self.extra_hp as f64
-        + 5.0 * self.extra_will as f64
-        + 5.0 * self.extra_per as f64
-        + 3.0 * self.extra_fp as f64
-        + 5.0 * self.extra_speed as f64
-        + 5.0 * self.extra_move as f64
+        self.ledger().iter().map(|(_, c)| c).sum()
     }
 }
 
 #[cfg(test)]
 mod ch_tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::{adq::Adq, attrib::{AttributeType, AttributeValued}, config::Config, context::{Context, ContextPayload}, damage::{Damage, DamageDelivery, DamageResistance, DamageType, PassiveDefense}, dta::genre::Genre, edition::GurpsEd, encumbrance::Encumbrance, equipment::{armor::Armor, item::{container::Container, Item}, spec::{Spec, SpecFlag}, weapon::{melee::{parry::Parry, reach::Reach, Melee}, Weapon}, Equipment}, gender::Gender, inventory::StowError, misc::{category::{Category, CategoryPayload}, costly::Costly}, modifier::race::RacialTemplate, secondary::{PurchaseError, Secondary}};
+
     use super::Ch;
 
+    fn melee(name: &str) -> Equipment {
+        Equipment::Weapon(Weapon::Melee(Melee {
+            name: name.to_string(), damage: vec![], max_damage: None,
+            cost: Some(40.0), weight: Some(1.0), skill: None, notes: None,
+            mod_groups: vec![], acc: None, st_req: None, spec: Default::default(),
+            reach: vec![], parry: Default::default(), two_handed: false,
+        }))
+    }
+
+    fn armor(name: &str, cover: &[i32], dr: DamageResistance, pd: i32) -> Equipment {
+        Equipment::Armor(Armor {
+            name: name.to_string(), dr: Some(dr), pd: Some(PassiveDefense::from(pd)),
+            cover: HashSet::from_iter(cover.iter().copied()), cost: None, weight: None,
+            mod_groups: vec![], skill: None, stats_affected: vec![], skills_affected: vec![],
+            container: None, spec: Default::default(),
+        })
+    }
+
+    fn item(name: &str, weight: f64, container: Option<Container>) -> Equipment {
+        Equipment::Item(Item {
+            name: name.to_string(), notes: None, cost: None, weight: Some(weight),
+            skill: None, mod_groups: vec![], container, spec: Default::default(),
+        })
+    }
+
+    fn genre_with(items: &[&str]) -> Genre {
+        let mut genre = Genre::new();
+        let mut cat = Category::new("Weapons");
+        for i in items {
+            cat.items.insert(i.to_string(), CategoryPayload::Equipment(melee(i)));
+        }
+        let mut ctx = ContextPayload::new(Context::Equipment);
+        ctx.items.insert(cat.name.clone(), cat);
+        genre.items = HashMap::from([(Context::Equipment, ctx)]);
+        genre
+    }
+
     #[test]
     fn init_works() {
         let ch = Ch::new("Nameless");
@@ -111,22 +706,233 @@ mod ch_tests {
 
     #[test]
     fn speed_works() {
+        let ed4 = Config::default_tl8(GurpsEd::Ed4);
         let mut ch = Ch::new("Nameless");
         assert_eq!(5.0, ch.speed());
-        assert_eq!(5, ch.mov());
+        assert_eq!(5, ch.mov(&ed4));
 
         ch.dx += 2;
         assert_eq!(5.5, ch.speed());
-        assert_eq!(5, ch.mov());
+        assert_eq!(5, ch.mov(&ed4));
 
         ch.ht += 2;
         assert_eq!(6.0, ch.speed());
-        assert_eq!(6, ch.mov());
+        assert_eq!(6, ch.mov(&ed4));
 
         ch.extra_speed = 1;
         assert_eq!(6.25, ch.speed());
         
         ch.extra_move = 1;
-        assert_eq!(7, ch.mov());
+        assert_eq!(7, ch.mov(&ed4));
+    }
+
+    #[test]
+    fn apply_package_works() {
+        let genre = genre_with(&["Broadsword", "Dagger"]);
+        let package = Adq {
+            name: "Gambler".to_string(),
+            initial_cost: 10, cost_increment: 0, level: 0, max_level: 1,
+            bonus_mods: vec![], given: vec![], mod_groups: vec![],
+            equipment_given: vec![("Broadsword".to_string(), 2), ("Dagger".to_string(), 3), ("Large knife".to_string(), 1)],
+            cash_cost: 150.0,
+        };
+        let mut ch = Ch::new("Nameless");
+        let unknown = ch.apply_package(&package, &genre);
+        assert_eq!(vec!["Large knife".to_string()], unknown);
+        assert_eq!(2, ch.inventory.count("Broadsword"));
+        assert_eq!(3, ch.inventory.count("Dagger"));
+        assert_eq!(10.0, ch.cost());
+        assert_eq!(-150.0, ch.cash);
+    }
+
+    #[test]
+    fn location_defenses_work() {
+        let mut ch = Ch::new("Nameless");
+        ch.inventory.add(armor("Vest", &[9, 10, 11, 17, 18], DamageResistance::from(4).vs(DamageType::Imp, 2), 2), 1).worn = true;
+        ch.inventory.add(armor("Jacket", &[6, 8, 9, 10, 11], DamageResistance::from(1), 1), 1).worn = true;
+        ch.inventory.add(armor("Spare vest", &[9, 10, 11], DamageResistance::from(10), 5), 1);
+        let helmet = ch.inventory.add(armor("Helmet", &[3, 4], DamageResistance::from(3), 3), 1);
+        helmet.worn = true;
+        if let Equipment::Armor(a) = &mut helmet.equipment {
+            a.spec = Spec { flags: vec![SpecFlag::FaceProtection], ..Default::default() }.into()
+        }
+
+        let chart = ch.location_defenses();
+        let at = |name: &str| chart.iter().find(|l| l.location.name == name).unwrap();
+        assert_eq!(5, at("Torso").dr.against(DamageType::Cr));
+        assert_eq!(3, at("Torso").dr.against(DamageType::Imp));
+        assert_eq!(2, at("Torso").pd.value());
+        assert_eq!(1, at("Right arm").dr.value());
+        assert_eq!(3, at("Face").dr.value());
+        assert_eq!(0, at("Feet").dr.value());
+    }
+
+    #[test]
+    fn weapon_damage_works() {
+        let mut ch = Ch::new("Nameless");
+        ch.st += 2;
+        let Equipment::Weapon(mut sword) = melee("Broadsword") else { unreachable!() };
+        if let Weapon::Melee(m) = &mut sword {
+            m.damage = vec![Damage::Cut(DamageDelivery::Sw(1)), Damage::Cr(DamageDelivery::Thr(1))];
+            m.st_req = Some(14);
+        }
+        let dmg = ch.weapon_damage(&sword, &GurpsEd::Ed4);
+        assert_eq!("2d-1 cut", dmg.damage[0].to_string());
+        assert_eq!("1d cr", dmg.damage[1].to_string());
+        assert_eq!(-2, dmg.skill_penalty);
+    }
+
+    #[test]
+    fn encumbrance_works() {
+        let ed4 = Config::default_tl8(GurpsEd::Ed4);
+        let mut ch = Ch::new("Nameless");
+        ch.inventory.add(item("Backpack", 3.0, Some(Container::Wt(40))), 1);
+        ch.inventory.add(item("Canteen", 1.0, Some(Container::Liquid(1))), 1);
+        assert_eq!(Ok(()), ch.inventory.stow("Backpack", item("Rock", 15.0, None), 2));
+        assert_eq!(Err(StowError::Overfull { capacity: 40.0, load: 45.0 }), ch.inventory.stow("Backpack", item("Rock", 15.0, None), 1));
+        assert_eq!(Err(StowError::LiquidOnly), ch.inventory.stow("Canteen", item("Rock", 15.0, None), 1));
+        assert_eq!(Err(StowError::NotFound), ch.inventory.stow("Rock", item("Pebble", 0.1, None), 1));
+        ch.inventory.add(item("Torch", 1.0, None), 1);
+        assert_eq!(Err(StowError::NotAContainer), ch.inventory.stow("Torch", item("Pebble", 0.1, None), 1));
+        assert_eq!(2, ch.inventory.count("Rock"));
+        assert_eq!(35.0, ch.carried_weight());
+        assert_eq!(Encumbrance::Light, ch.encumbrance(&ed4));
+        assert_eq!(5, ch.basic_move());
+        assert_eq!(4, ch.mov(&ed4));
+        assert_eq!(-1, ch.encumbrance(&ed4).dodge_penalty());
+    }
+
+    #[test]
+    fn parries_work() {
+        let (ed3, ed4) = (Config::default_tl3(GurpsEd::Ed3), Config::default_tl8(GurpsEd::Ed4));
+        let mut ch = Ch::new("Nameless");
+        let mut add = |name: &str, skill: &str, parry: Parry, st_req: Option<i32>| {
+            let Equipment::Weapon(Weapon::Melee(mut m)) = melee(name) else { unreachable!() };
+            m.skill = Some(skill.to_string());
+            m.parry = parry;
+            m.st_req = st_req;
+            ch.inventory.add(Equipment::Weapon(Weapon::Melee(m)), 1);
+        };
+        add("Broadsword", "Broadsword", Parry::Yes(0), None);
+        add("Rapier", "Fencing", Parry::Fencing(0), None);
+        add("Large knife", "Knife", Parry::Yes(-1), None);
+        add("Maul", "Two-Handed Axe/Mace", Parry::Unbalanced(0), Some(13));
+        add("Whip", "Whip", Parry::No, None);
+        ch.inventory.add(item("Rope", 1.0, None), 1);
+
+        let skills = |s: &str| match s {
+            "Whip" => Some(12),
+            "Knife" => Some(11),
+            "Jitte" => Some(20),
+            _ => Some(13),
+        };
+        assert_eq!(vec![("Broadsword", Some(9)), ("Rapier", Some(9)), ("Large knife", Some(7)), ("Maul", Some(8)), ("Whip", None)],
+            ch.parries(skills, &ed4));
+        assert_eq!(vec![("Broadsword", Some(6)), ("Rapier", Some(8)), ("Large knife", Some(4)), ("Maul", Some(5)), ("Whip", None)],
+            ch.parries(skills, &ed3));
+        assert!(ch.parries(|s| if s == "Knife" { Some(12) } else { None }, &ed4).eq(&vec![("Large knife", Some(8))]));
+    }
+
+    #[test]
+    fn secondary_purchases_work() {
+        let (ed3, ed4) = (Config::default_tl3(GurpsEd::Ed3), Config::default_tl8(GurpsEd::Ed4));
+        let mut ch = Ch::new("Nameless");
+        ch.st += 2;
+        assert_eq!(12, ch.hp(&ed4));
+        assert_eq!(10, ch.hp(&ed3));
+        assert_eq!(12, ch.fp(&ed3));
+
+        assert_eq!(Ok(2), ch.buy_extra(Secondary::HP, 2, &ed4));
+        assert_eq!(14, ch.hp(&ed4));
+        assert_eq!(Err(PurchaseError::OutOfBounds { min: -3, max: 3 }), ch.buy_extra(Secondary::HP, 2, &ed4));
+        assert_eq!(Ok(1), ch.buy_extra(Secondary::Will, 1, &ed4));
+        assert_eq!(11, ch.wp(&ed4));
+        assert_eq!(10, ch.wp(&ed3));
+        assert_eq!(10, ch.per(&ed4));
+        assert_eq!(Err(PurchaseError::NotInEdition), ch.buy_extra(Secondary::Per, 1, &ed3));
+        assert_eq!(Err(PurchaseError::Step(4)), ch.buy_extra(Secondary::Speed, 1, &ed3));
+        assert_eq!(Ok(4), ch.buy_extra(Secondary::Speed, 4, &ed3));
+        assert_eq!(6.0, ch.speed());
+        assert_eq!(20.0 + 4.0 + 5.0 + 25.0, ch.cost());
+        assert_eq!(Ok(0), ch.buy_extra(Secondary::Speed, -4, &ed3));
+        assert_eq!(20.0 + 4.0 + 5.0, ch.cost());
+    }
+
+    #[test]
+    fn active_defenses_work() {
+        let (ed3, ed4) = (Config::default_tl3(GurpsEd::Ed3), Config::default_tl8(GurpsEd::Ed4));
+        let mut ch = Ch::new("Nameless");
+        assert_eq!(8, ch.dodge(&ed4));
+        assert_eq!(5, ch.dodge(&ed3));
+        assert_eq!(9, ch.block(12, &ed4));
+        assert_eq!(6, ch.block(12, &ed3));
+
+        // Medium encumbrance (ST 10: 40 < 50 ≤ 60 lbs).
+        ch.inventory.add(item("Anvil", 50.0, None), 1);
+        assert_eq!(6, ch.dodge(&ed4));
+        assert_eq!(3, ch.dodge(&ed3));
+
+        let advantage = |name: &str, level| Adq {
+            name: name.to_string(), initial_cost: 15, cost_increment: 15, level, max_level: 3,
+            bonus_mods: vec![], given: vec![], mod_groups: vec![], equipment_given: vec![], cash_cost: 0.0,
+        };
+        ch.advantages.push(advantage("Combat Reflexes", 1));
+        ch.advantages.push(advantage("Enhanced Dodge", 2));
+        assert_eq!(9, ch.dodge(&ed4));
+        assert_eq!(10, ch.block(12, &ed4));
+        assert_eq!(45.0, ch.cost());
+    }
+
+    #[test]
+    fn racial_template_works() {
+        let ed4 = Config::default_tl8(GurpsEd::Ed4);
+        let mut ch = Ch::new("Grug");
+        ch.set_race(Some(RacialTemplate::new("Ogre")
+            .with_bonus(AttributeType::ST, 4)
+            .with_bonus(AttributeType::IQ, -2)
+            .with_maximum(AttributeType::IQ, 10)
+            .with_cost_multiplier(AttributeType::IQ, 0.5)
+            .with_sm(2)
+            .with_no_fine_manipulators()));
+        assert_eq!(14, ch.st.value());
+        assert_eq!(8, ch.iq.value());
+        assert_eq!(2, ch.size_modifier());
+        assert_eq!(0.0, ch.cost());
+
+        assert_eq!(Ok(16), ch.raise_attribute(AttributeType::ST, 2, 20));
+        assert_eq!(Err(PurchaseError::OutOfBounds { min: 1, max: 10 }), ch.raise_attribute(AttributeType::IQ, 3, 20));
+        assert_eq!(Ok(10), ch.raise_attribute(AttributeType::IQ, 2, 20));
+        assert_eq!(Ok(2), ch.buy_extra(Secondary::HP, 2, &ed4));
+        // ST 20×0.8×0.6, IQ 40×0.5, HP 4×0.8×0.6
+        assert!((ch.cost() - (9.6 + 20.0 + 1.92)).abs() < 1e-9);
+
+        let Equipment::Weapon(Weapon::Melee(mut club)) = melee("Club") else { unreachable!() };
+        club.reach = vec![Reach::C, Reach::Yards(1)];
+        assert_eq!(vec![Reach::C, Reach::Yards(3)], ch.reach(&club));
+
+        ch.set_race(None);
+        assert_eq!(12, ch.st.value());
+        assert_eq!(Err(PurchaseError::OutOfBounds { min: 1, max: 20 }), ch.raise_attribute(AttributeType::ST, 9, 20));
+        assert_eq!(vec![Reach::C, Reach::Yards(1)], ch.reach(&club));
+    }
+
+    #[test]
+    fn gender_works() {
+        let (ed3, ed4) = (Config::default_tl3(GurpsEd::Ed3), Config::default_tl8(GurpsEd::Ed4));
+        let mut ch = Ch::new("Nameless");
+        ch.set_gender(Some(Gender::Female), &ed3);
+        assert_eq!(Some(&Gender::Female), ch.gender());
+        assert_eq!(-5.0, ch.cost());
+        assert!(ch.ledger().contains(&("Gender: Female".to_string(), -5.0)));
+
+        ch.set_gender(Some(Gender::Female), &ed4);
+        assert_eq!(0.0, ch.cost());
+        assert_eq!(4, ch.ledger().len());
+
+        let ed4 = ed4.with_gender_points(Gender::Other("Neuter".into()), 5);
+        ch.set_gender(Some(Gender::Other("Neuter".into())), &ed4);
+        assert_eq!(5.0, ch.cost());
+        ch.set_gender(None, &ed4);
+        assert_eq!(0.0, ch.cost());
     }
 }
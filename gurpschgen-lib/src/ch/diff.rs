@@ -0,0 +1,384 @@
+use std::collections::BTreeMap;
+
+use crate::{adq::Adq, advancement::Advancement, attrib::{AttributeType, AttributeValued}, equipment::Equipment, gender::Gender, inventory::{Inventory, Possession}, misc::{costly::Costly, named::Named}, modifier::race::RacialTemplate, secondary::Secondary, skill::Skill};
+
+use super::Ch;
+
+
+/**
+ A single structural change between two [Ch].
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// Change in levels bought (or sold) on top of an attribute's (racial) base.
+    Attribute { attrib: AttributeType, from: i32, to: i32 },
+    AdqAdded(Adq),
+    AdqRemoved(Adq),
+    AdqLevel { name: String, from: usize, to: usize },
+    PackageAdded(Adq),
+    PackageRemoved(Adq),
+    PackageLevel { name: String, from: usize, to: usize },
+    SkillAdded(Skill),
+    SkillRemoved(Skill),
+    SkillRank { name: String, from: usize, to: usize },
+    /// Change in (total, containers included) quantity of equipment `name`.
+    Equipment { name: String, from: usize, to: usize },
+    /// Change in extra levels of a secondary characteristic, along with the `points` it cost (or gained).
+    Extra { stat: Secondary, from: i32, to: i32, points: f64 },
+    Race { from: Option<Box<RacialTemplate>>, to: Option<Box<RacialTemplate>> },
+    /// Change of gender; `points` are what the new gender is worth.
+    Gender { from: Option<Gender>, to: Option<Gender>, points: i32 },
+    Cash { from: f64, to: f64 },
+    /// Change in the advancement journal &ndash; it's taken as a whole.
+    Journal { from: Vec<Advancement>, to: Vec<Advancement> },
+}
+
+impl Change {
+    /**
+     Get what the change is about &ndash; two changes of the same subject touch the same thing.
+     */
+    pub fn subject(&self) -> String {
+        match self {
+            Self::Attribute { attrib, .. } => format!("{attrib:?}"),
+            Self::AdqAdded(a) |
+            Self::AdqRemoved(a) => format!("Adq: {}", a.name()),
+            Self::AdqLevel { name, .. } => format!("Adq: {name}"),
+            Self::PackageAdded(a) |
+            Self::PackageRemoved(a) => format!("Package: {}", a.name()),
+            Self::PackageLevel { name, .. } => format!("Package: {name}"),
+            Self::SkillAdded(s) |
+            Self::SkillRemoved(s) => format!("Skill: {}", s.name()),
+            Self::SkillRank { name, .. } => format!("Skill: {name}"),
+            Self::Equipment { name, .. } => format!("Equipment: {name}"),
+            Self::Extra { stat, .. } => format!("Extra {stat:?}"),
+            Self::Race { .. } => "Race".to_string(),
+            Self::Gender { .. } => "Gender".to_string(),
+            Self::Cash { .. } => "Cash".to_string(),
+            Self::Journal { .. } => "Journal".to_string(),
+        }
+    }
+}
+
+/**
+ Structural difference between two [Ch].
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChDiff {
+    pub changes: Vec<Change>,
+    /// Per-entry cost deltas of the [ledger][Ch::ledger]; unchanged entries left out.
+    pub cost_deltas: Vec<(String, f64)>,
+    /// Total cost delta.
+    pub cost_delta: f64,
+}
+
+impl ChDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.cost_delta == 0.0
+    }
+}
+
+/**
+ A [Change] made on both copies of a [Ch], differently.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub ours: Change,
+    pub theirs: Change,
+}
+
+/**
+ Diff two named lists (of advantages, skills, …) by name.
+ */
+fn diff_named<T, F>(old: &[T], new: &[T], changes: &mut Vec<Change>, added: fn(T) -> Change, removed: fn(T) -> Change, changed: F)
+where
+    T: Named + Clone + PartialEq,
+    F: Fn(&T, &T) -> Option<Change>,
+{
+    for o in old {
+        match new.iter().find(|n| n.name() == o.name()) {
+            None => changes.push(removed(o.clone())),
+            Some(n) => if let Some(c) = changed(o, n) { changes.push(c) }
+        }
+    }
+    for n in new.iter().filter(|n| !old.iter().any(|o| o.name() == n.name())) {
+        changes.push(added(n.clone()))
+    }
+}
+
+/**
+ Count equipment by name, containers' contents included.
+ */
+fn equipment_counts(inventory: &Inventory) -> BTreeMap<String, usize> {
+    fn count_in(items: &[Possession], counts: &mut BTreeMap<String, usize>) {
+        for p in items {
+            *counts.entry(p.name().to_string()).or_default() += p.quantity;
+            count_in(&p.contents, counts);
+        }
+    }
+    let mut counts = BTreeMap::new();
+    count_in(inventory.items(), &mut counts);
+    counts
+}
+
+/**
+ Find (the first) equipment called `name`, containers' contents included.
+ */
+fn find_equipment<'a>(items: &'a [Possession], name: &str) -> Option<&'a Equipment> {
+    items.iter().find_map(|p| if p.name() == name {
+        Some(&p.equipment)
+    } else {
+        find_equipment(&p.contents, name)
+    })
+}
+
+/**
+ Get the structural difference from `old` to `new`.
+ */
+pub fn diff(old: &Ch, new: &Ch) -> ChDiff {
+    let mut changes = vec![];
+    if old.race != new.race {
+        changes.push(Change::Race { from: old.race.clone().map(Box::new), to: new.race.clone().map(Box::new) });
+    }
+    if old.gender != new.gender || old.gender_points != new.gender_points {
+        changes.push(Change::Gender { from: old.gender.clone(), to: new.gender.clone(), points: new.gender_points });
+    }
+    for attrib in AttributeType::ALL {
+        let (from, to) = (old.attribute(attrib).rel_val(), new.attribute(attrib).rel_val());
+        if from != to {
+            changes.push(Change::Attribute { attrib, from, to });
+        }
+    }
+    for stat in Secondary::ALL {
+        let (from, to) = (old.extra(stat), new.extra(stat));
+        let points_of = |ch: &Ch| ch.extra_points.get(&stat).copied().unwrap_or(0.0);
+        let points = points_of(new) - points_of(old);
+        if from != to || points != 0.0 {
+            changes.push(Change::Extra { stat, from, to, points });
+        }
+    }
+    diff_named(&old.advantages, &new.advantages, &mut changes, Change::AdqAdded, Change::AdqRemoved,
+        |o, n| (o.level != n.level).then(|| Change::AdqLevel { name: o.name.clone(), from: o.level, to: n.level }));
+    diff_named(&old.packages, &new.packages, &mut changes, Change::PackageAdded, Change::PackageRemoved,
+        |o, n| (o.level != n.level).then(|| Change::PackageLevel { name: o.name.clone(), from: o.level, to: n.level }));
+    diff_named(&old.skills, &new.skills, &mut changes, Change::SkillAdded, Change::SkillRemoved,
+        |o, n| (o.rank != n.rank).then(|| Change::SkillRank { name: o.name.clone(), from: o.rank, to: n.rank }));
+    let (old_eq, new_eq) = (equipment_counts(&old.inventory), equipment_counts(&new.inventory));
+    for name in old_eq.keys().chain(new_eq.keys().filter(|n| !old_eq.contains_key(*n))) {
+        let (from, to) = (*old_eq.get(name).unwrap_or(&0), *new_eq.get(name).unwrap_or(&0));
+        if from != to {
+            changes.push(Change::Equipment { name: name.clone(), from, to });
+        }
+    }
+    if old.cash != new.cash {
+        changes.push(Change::Cash { from: old.cash, to: new.cash });
+    }
+    if old.journal != new.journal {
+        changes.push(Change::Journal { from: old.journal.clone(), to: new.journal.clone() });
+    }
+
+    let (old_ledger, new_ledger) = (old.ledger(), new.ledger());
+    let cost_of = |ledger: &[(String, f64)], entry: &str| ledger.iter().find(|(e, _)| e == entry).map_or(0.0, |(_, c)| *c);
+    let mut cost_deltas: Vec<(String, f64)> = vec![];
+    for (entry, _) in old_ledger.iter().chain(new_ledger.iter()) {
+        let delta = cost_of(&new_ledger, entry) - cost_of(&old_ledger, entry);
+        if delta != 0.0 && !cost_deltas.iter().any(|(e, _)| e == entry) {
+            cost_deltas.push((entry.clone(), delta));
+        }
+    }
+
+    ChDiff { changes, cost_deltas, cost_delta: new.cost() - old.cost() }
+}
+
+/**
+ Three-way merge &ndash; apply changes made from `base` to `theirs` onto `ours`.
+
+ Changes both sides made alike are taken once; changes to the same thing made differently are conflicts.
+
+ **Returns** the merged [Ch], or all the conflicts found.
+ */
+pub fn merge(base: &Ch, ours: &Ch, theirs: &Ch) -> Result<Ch, Vec<Conflict>> {
+    let our_changes = diff(base, ours).changes;
+    let mut merged = ours.clone();
+    let mut conflicts = vec![];
+    for change in diff(base, theirs).changes {
+        match our_changes.iter().find(|c| c.subject() == change.subject()) {
+            Some(c) if *c == change => (),
+            Some(c) => conflicts.push(Conflict { ours: c.clone(), theirs: change }),
+            None => merged.apply_change(&change, theirs),
+        }
+    }
+    if conflicts.is_empty() { Ok(merged) } else { Err(conflicts) }
+}
+
+impl Ch {
+    /**
+     Apply `change` (made by `source`) to `self`.
+     */
+    fn apply_change(&mut self, change: &Change, source: &Ch) {
+        match change {
+            Change::Attribute { attrib, from, to } => *self.attribute_mut(*attrib) += to - from,
+            Change::AdqAdded(a) => self.advantages.push(a.clone()),
+            Change::AdqRemoved(a) => self.advantages.retain(|x| x.name() != a.name()),
+            Change::AdqLevel { name, to, .. } => if let Some(a) = self.advantages.iter_mut().find(|a| a.name == *name) {
+                a.level = *to
+            },
+            Change::PackageAdded(a) => self.packages.push(a.clone()),
+            Change::PackageRemoved(a) => self.packages.retain(|x| x.name() != a.name()),
+            Change::PackageLevel { name, to, .. } => if let Some(a) = self.packages.iter_mut().find(|a| a.name == *name) {
+                a.level = *to
+            },
+            Change::SkillAdded(s) => self.skills.push(s.clone()),
+            Change::SkillRemoved(s) => self.skills.retain(|x| x.name() != s.name()),
+            Change::SkillRank { name, to, .. } => if let Some(s) = self.skills.iter_mut().find(|s| s.name == *name) {
+                s.rank = *to
+            },
+            Change::Equipment { name, from, to } => if to > from {
+                if let Some(e) = find_equipment(source.inventory.items(), name) {
+                    self.inventory.add(e.clone(), to - from);
+                }
+            } else {
+                self.inventory.take_anywhere(name, from - to);
+            },
+            Change::Extra { stat, from, to, points } => {
+                match stat {
+                    Secondary::HP => self.extra_hp += to - from,
+                    Secondary::Will => self.extra_will += to - from,
+                    Secondary::Per => self.extra_per += to - from,
+                    Secondary::FP => self.extra_fp += to - from,
+                    Secondary::Speed => self.extra_speed += to - from,
+                    Secondary::Move => self.extra_move += to - from,
+                }
+                *self.extra_points.entry(*stat).or_default() += points;
+            },
+            Change::Race { to, .. } => self.set_race(to.as_deref().cloned()),
+            Change::Gender { to, points, .. } => {
+                self.gender = to.clone();
+                self.gender_points = *points;
+            },
+            Change::Cash { from, to } => self.cash += to - from,
+            Change::Journal { to, .. } => self.journal = to.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod diff_tests {
+    use crate::{adq::Adq, advancement::{Advancement, Purchase}, attrib::{AttributeType, AttributeValued}, ch::Ch, config::Config, edition::GurpsEd, equipment::{item::container::Container, Equipment}, fixtures::{self, item}, gender::Gender, misc::{costly::Costly, named::Named}, modifier::race::RacialTemplate, secondary::Secondary};
+
+    use super::{diff, merge, Change, Conflict};
+
+    fn adq(name: &str, cost: i32, level: usize) -> Adq {
+        Adq { level, ..fixtures::adq(name, cost) }
+    }
+
+    fn rope() -> Equipment {
+        item("Rope", 10.0, 5.0)
+    }
+
+    #[test]
+    fn diff_works() {
+        let mut old = Ch::new("Nameless");
+        old.advantages.push(adq("Alertness", 5, 1));
+        old.advantages.push(adq("Bad Temper", -10, 1));
+        let mut new = old.clone();
+        new.dx += 1;
+        new.advantages[0].level = 2;
+        new.advantages.remove(1);
+        new.advantages.push(adq("Toughness", 10, 1));
+        new.inventory.add(rope(), 2);
+
+        let d = diff(&old, &new);
+        assert_eq!(vec![
+            Change::Attribute { attrib: AttributeType::DX, from: 0, to: 1 },
+            Change::AdqLevel { name: "Alertness".into(), from: 1, to: 2 },
+            Change::AdqRemoved(adq("Bad Temper", -10, 1)),
+            Change::AdqAdded(adq("Toughness", 10, 1)),
+            Change::Equipment { name: "Rope".into(), from: 0, to: 2 },
+        ], d.changes);
+        assert_eq!(20.0 + 5.0 + 10.0 + 10.0, d.cost_delta);
+        assert!(d.cost_deltas.contains(&("Bad Temper".to_string(), 10.0)));
+        assert!(diff(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn merge_works() {
+        let mut base = Ch::new("Nameless");
+        base.advantages.push(adq("Alertness", 5, 1));
+        base.inventory.add(rope(), 1);
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        ours.st += 2;
+        ours.iq += 1;
+        theirs.iq += 1;
+        theirs.advantages[0].level = 3;
+        theirs.inventory.add(rope(), 2);
+
+        let merged = merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(12, merged.st.value());
+        assert_eq!(11, merged.iq.value());
+        assert_eq!(3, merged.advantages[0].level);
+        assert_eq!(3, merged.inventory.count("Rope"));
+
+        theirs.st += 1;
+        assert_eq!(Err(vec![Conflict {
+            ours: Change::Attribute { attrib: AttributeType::ST, from: 0, to: 2 },
+            theirs: Change::Attribute { attrib: AttributeType::ST, from: 0, to: 1 },
+        }]), merge(&base, &ours, &theirs).map(|_| ()));
+    }
+
+    #[test]
+    fn merge_covers_the_rest_works() {
+        let config = Config::default_tl8(GurpsEd::Ed4);
+        let base = Ch::new("Nameless");
+        let (mut ours, mut theirs) = (base.clone(), base.clone());
+        ours.st += 1;
+        ours.buy_extra(Secondary::HP, 2, &config).unwrap();
+        let mut race = RacialTemplate::new("Ogre");
+        race.bonuses.insert(AttributeType::ST, 2);
+        theirs.set_race(Some(race.clone()));
+        theirs.st += 1;
+        theirs.set_gender(Some(Gender::Female), &config.clone().with_gender_points(Gender::Female, -5));
+        theirs.packages.push(adq("Soldier", 20, 1));
+        theirs.cash = 250.0;
+        theirs.advance(Advancement::new("2024-01-01", 20).with(Purchase::Attribute { attrib: AttributeType::DX, levels: 1 }), 20).unwrap();
+
+        let merged = merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(Some(&race), merged.race());
+        // Racial bonus and the levels both sides bought aren't mixed up.
+        assert_eq!(10 + 2 + 1, merged.st.value());
+        assert_eq!(11, merged.dx.value());
+        assert_eq!(2, merged.extra(Secondary::HP));
+        assert_eq!(Some(&Gender::Female), merged.gender());
+        assert_eq!(vec!["Soldier"], merged.packages.iter().map(|p| p.name()).collect::<Vec<_>>());
+        assert_eq!(250.0, merged.cash);
+        assert_eq!(1, merged.journal().len());
+        // Both bought the same ST level; only ours bought HP.
+        assert_eq!(theirs.cost() + 4.0, merged.cost());
+
+        // Different secondaries bought on each side are independent; the same one bought differently isn't.
+        theirs.buy_extra(Secondary::FP, 1, &config).unwrap();
+        let merged = merge(&base, &ours, &theirs).unwrap();
+        assert_eq!((2, 1), (merged.extra(Secondary::HP), merged.extra(Secondary::FP)));
+        assert_eq!(theirs.cost() + 4.0, merged.cost());
+        theirs.buy_extra(Secondary::HP, 1, &config).unwrap();
+        assert_eq!(vec!["Extra HP".to_string()],
+            merge(&base, &ours, &theirs).unwrap_err().iter().map(|c| c.ours.subject()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn merge_takes_stowed_equipment_works() {
+        let packed = |ropes| {
+            let mut backpack = item("Backpack", 60.0, 3.0);
+            if let Equipment::Item(i) = &mut backpack {
+                i.container = Some(Container::Wt(40));
+            }
+            let mut ch = Ch::new("Nameless");
+            ch.inventory.add(backpack, 1);
+            ch.inventory.stow("Backpack", rope(), ropes).unwrap();
+            ch
+        };
+        let (base, theirs) = (packed(2), packed(1));
+        let merged = merge(&base, &base.clone(), &theirs).unwrap();
+        assert_eq!(1, merged.inventory.count("Rope"));
+        assert_eq!(1, merged.inventory.items().len());
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{attrib::{AttributeType, AttributeValued}, config::Config, context::Context, dta::genre::Genre, equipment::Equipment, misc::{category::CategoryPayload, leveled::Leveled, named::Named}, skill::{Skill, SkillLevel, SkillRoot, Stat}};
+
+use super::Ch;
+
+/**
+ Get the attribute `skill` is based on; martial arts' maneuvers go by DX.
+ */
+fn skill_attribute(skill: &Skill) -> AttributeType {
+    match &skill.base {
+        SkillRoot::M { stat, .. } |
+        SkillRoot::P { stat, .. } => match stat {
+            Stat::DX => AttributeType::DX,
+            Stat::HT => AttributeType::HT,
+            Stat::IQ => AttributeType::IQ,
+            Stat::ST => AttributeType::ST,
+        },
+        SkillRoot::MA { .. } => AttributeType::DX,
+    }
+}
+
+/**
+ Split `text` at `sep`arators which aren't within parentheses.
+ */
+fn split_outside_parens(text: &str, sep: char) -> Vec<&str> {
+    let (mut parts, mut depth, mut start) = (vec![], 0, 0);
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if c == sep && depth == 0 => {
+                parts.push(text[start..i].trim());
+                start = i + c.len_utf8();
+            },
+            _ => ()
+        }
+    }
+    parts.push(text[start..].trim());
+    parts.into_iter().filter(|p| !p.is_empty()).collect()
+}
+
+impl Ch {
+    /**
+     Get effective level of `skill` &ndash; attribute plus (or minus) whatever the skill's rank gives.
+     */
+    pub fn skill_value(&self, skill: &Skill, config: &Config) -> Option<i32> {
+        Some(self.attribute(skill_attribute(skill)).value() + skill.level(config)?)
+    }
+
+    /**
+     Render `Ch` as a compact, one paragraph stat block, e.g.
+
+     `ST 12, DX 11, IQ 9, HT 11; Broadsword-13, Brawling-12; Leather Armor (PD2 DR2)`
+
+     Sections are attributes; advantages; skills; equipment &ndash; empty ones left out.
+     */
+    pub fn stat_block(&self, config: &Config) -> String {
+        let mut sections = vec![AttributeType::ALL.iter()
+            .map(|a| format!("{a:?} {}", self.attribute(*a).value()))
+            .collect::<Vec<_>>()];
+        sections.push(self.advantages.iter().filter(|a| a.level() > 0).map(|a| match a.level() {
+            1 => a.name().to_string(),
+            n => format!("{}@{n}", a.name()),
+        }).collect());
+        sections.push(self.skills.iter().filter(|s| s.rank > 0).map(|s| match self.skill_value(s, config) {
+            Some(v) => format!("{}-{v}", s.name()),
+            None => s.name().to_string(),
+        }).collect());
+        sections.push(self.inventory.items().iter().map(|p| {
+            let mut entry = p.name().to_string();
+            if let Equipment::Armor(_) = p.equipment {
+                entry += &format!(" (PD{} DR{})", p.pd().map_or(0, |x| x.value()), p.dr().map_or(0, |x| x.value()));
+            }
+            if p.quantity > 1 {
+                entry += &format!(" ×{}", p.quantity);
+            }
+            entry
+        }).collect());
+        sections.into_iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.join(", "))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /**
+     Parse a [stat block][Ch::stat_block] into a `Ch` called `name`, resolving names against `genre`.
+
+     Entries are recognized by what they are rather than by which section they're in. Parenthesized
+     notes (PD/DR etc.) are informational only &ndash; whatever `genre` says goes.
+
+     **Returns** the `Ch` along with whatever (names) couldn't be resolved &ndash; skills at levels no rank gets to included.
+     */
+    pub fn from_stat_block(name: &str, text: &str, genre: &Genre, config: &Config) -> (Ch, Vec<String>) {
+        let mut ch = Ch::new(name);
+        let mut unknown = vec![];
+        for entry in split_outside_parens(text, ';').into_iter().flat_map(|s| split_outside_parens(s, ',')) {
+            if let Some(attrib) = AttributeType::ALL.iter().find(|a| entry.strip_prefix(&format!("{a:?}")).is_some_and(|v| v.trim().parse::<i32>().is_ok())) {
+                let value: i32 = entry[2..].trim().parse().unwrap_or(10);
+                let delta = value - ch.attribute(*attrib).value();
+                *ch.attribute_mut(*attrib) += delta;
+                continue;
+            }
+            match ch.take_skill(entry, genre, config) {
+                Some(true) => continue,
+                Some(false) => {
+                    unknown.push(entry.to_string());
+                    continue
+                },
+                None => ()
+            }
+            let (item, quantity) = match entry.rsplit_once(" ×") {
+                Some((n, q)) if q.trim().parse::<usize>().is_ok() => (n.trim(), q.trim().parse().unwrap_or(1)),
+                _ => (entry.trim(), 1)
+            };
+            // Names may have parentheses of their own, e.g. "Medium Body Armor (TL8)" - only a PD/DR note is dropped.
+            let noteless = match item.rsplit_once(" (") {
+                Some((n, note)) if note.starts_with("PD") && note.ends_with(')') => n,
+                _ => item
+            };
+            if let Some(e) = genre.find_equipment(item).or_else(|| genre.find_equipment(noteless)) {
+                let worn = matches!(e, Equipment::Armor(_));
+                ch.inventory.add(e.clone(), quantity).worn = worn;
+                continue;
+            }
+            let (adq, level) = match item.rsplit_once('@') {
+                Some((n, l)) if l.parse::<usize>().is_ok() => (n, l.parse().unwrap_or(1)),
+                _ => (item, 1)
+            };
+            match genre.find(&Context::Advantage, adq).or_else(|| genre.find(&Context::Disadvantage, adq)) {
+                Some(CategoryPayload::Advantage(a) | CategoryPayload::Disadvantage(a)) => {
+                    let mut a = a.clone();
+                    a.level = level;
+                    ch.advantages.push(a);
+                },
+                _ => unknown.push(entry.to_string())
+            }
+        }
+        (ch, unknown)
+    }
+
+    /**
+     Take a `Skill-level` `entry` in, at whatever rank gets to that level.
+
+     **Returns** `None` if `entry` isn't a skill known to `genre`, otherwise whether some rank (1 or more) matched its level.
+     */
+    fn take_skill(&mut self, entry: &str, genre: &Genre, config: &Config) -> Option<bool> {
+        let (name, level) = entry.rsplit_once('-')?;
+        let level = level.trim().parse::<i32>().ok()?;
+        let Some(CategoryPayload::Skill(skill)) = genre.find(&Context::Skill, name) else { return None };
+        match skill.rank_reaching(level, |s| self.skill_value(s, config)) {
+            Some((rank, v)) if v == level => {
+                self.skills.push(Skill { rank, ..skill.clone() });
+                Some(true)
+            },
+            _ => Some(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod statblock_tests {
+    use std::collections::HashSet;
+
+    use crate::{adq::Adq, attrib::AttributeValued, ch::Ch, config::Config, context::Context, damage::{DamageResistance, PassiveDefense}, dta::genre::Genre, edition::GurpsEd, equipment::{armor::Armor, Equipment}, fixtures::{self, adq, item, skill}, misc::category::CategoryPayload, skill::{DifficultyRating, SkillRoot, Stat}};
+
+    fn genre() -> Genre {
+        let armor = |name: &str, pd, dr| CategoryPayload::Equipment(Equipment::Armor(Armor {
+            name: name.to_string(), dr: Some(DamageResistance::from(dr)), pd: Some(PassiveDefense::from(pd)),
+            cover: HashSet::from([9, 10, 11]), cost: Some(100.0), weight: Some(10.0),
+            mod_groups: vec![], skill: None, stats_affected: vec![], skills_affected: vec![],
+            container: None, spec: Default::default(),
+        }));
+        let toughness = Adq { cost_increment: 15, max_level: 2, ..adq("Toughness", 10) };
+        let skill = |name, diff| CategoryPayload::Skill(skill(name, SkillRoot::P { stat: Stat::DX, diff }));
+        fixtures::genre(vec![
+            (Context::Skill, "Combat", vec![("Broadsword", skill("Broadsword", DifficultyRating::A)), ("Brawling", skill("Brawling", DifficultyRating::E))]),
+            (Context::Equipment, "Armor", vec![("Leather Armor", armor("Leather Armor", 2, 2)), ("Medium Body Armor (TL8)", armor("Medium Body Armor (TL8)", 4, 12))]),
+            (Context::Equipment, "Gear", vec![("C cell (TL8)", CategoryPayload::Equipment(item("C cell (TL8)", 10.0, 0.5)))]),
+            (Context::Advantage, "Physical", vec![("Toughness", CategoryPayload::Advantage(toughness))]),
+        ])
+    }
+
+    #[test]
+    fn round_trip_works() {
+        let (genre, config) = (genre(), Config::default_tl8(GurpsEd::Ed4));
+        let text = "ST 12, DX 11, IQ 9, HT 11; Broadsword-13, Brawling-12; Leather Armor (PD2 DR2)";
+        let (ch, unknown) = Ch::from_stat_block("Goon", text, &genre, &config);
+        assert!(unknown.is_empty());
+        assert_eq!(12, ch.st.value());
+        assert_eq!(9, ch.iq.value());
+        assert_eq!(4, ch.skills[0].rank);
+        assert!(ch.inventory.items()[0].worn);
+        assert_eq!(text, ch.stat_block(&config));
+    }
+
+    #[test]
+    fn parenthesized_names_round_trip_works() {
+        let (genre, config) = (genre(), Config::default_tl8(GurpsEd::Ed4));
+        let text = "ST 10, DX 10, IQ 10, HT 10; Medium Body Armor (TL8) (PD4 DR12), C cell (TL8) ×2";
+        let (ch, unknown) = Ch::from_stat_block("Trooper", text, &genre, &config);
+        assert!(unknown.is_empty());
+        assert_eq!(2, ch.inventory.count("C cell (TL8)"));
+        assert_eq!(text, ch.stat_block(&config));
+    }
+
+    #[test]
+    fn unknown_names_are_reported() {
+        let (genre, config) = (genre(), Config::default_tl8(GurpsEd::Ed4));
+        let (ch, unknown) = Ch::from_stat_block("Goon", "ST 13; Toughness@2, Rich; Fencing-14, Brawling-10, Broadsword-99, Broadsword-5; Laser Rifle ×2", &genre, &config);
+        // No rank gets Broadsword that high (or that low).
+        assert_eq!(vec!["Rich", "Fencing-14", "Broadsword-99", "Broadsword-5", "Laser Rifle ×2"], unknown);
+        assert_eq!(2, ch.advantages[0].level);
+        assert_eq!(1, ch.skills[0].rank);
+        assert_eq!("ST 13, DX 10, IQ 10, HT 10; Toughness@2; Brawling-10", ch.stat_block(&config));
+    }
+}
@@ -1,21 +1,230 @@
-use crate::edition::GurpsEd;
+use std::{collections::HashSet, path::{Path, PathBuf}};
 
+use glob::glob;
+use serde::{Deserialize, Serialize};
+
+use crate::{ch::Ch, edition::GurpsEd, gender::Gender, misc::costly::Costly};
+
+/// Point value of being female in campaigns which treat it as a disadvantage.
+const FEMALE_DISADVANTAGE: i32 = -5;
+/// Starting points, unless the campaign says otherwise.
+const DEFAULT_POINTS: i32 = 100;
+
+/**
+ Campaign configuration &ndash; edition, TL, point budget, optional rules and what's (not) allowed.
+
+ Loadable from JSON or TOML, see [Config::load].
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Config {
     pub edition: GurpsEd,
-    pub female_as_5pts_disadvantage: bool,
     pub tl: i32,
+    /// Genre (file) the campaign uses.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    /// Starting point budget.
+    #[serde(default = "default_points")]
+    pub points: i32,
+    /// Max points gained from disadvantages (and other negative entries), as a positive number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disadvantage_cap: Option<i32>,
+    /// Optional rule: (3e) being female is a 5 point disadvantage.
+    #[serde(default)]
+    pub female_as_5pts_disadvantage: bool,
+    /// Campaign-specific point values of genders; these override `female_as_5pts_disadvantage`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gender_points: Vec<(Gender, i32)>,
+    /// If non-empty, only these (advantages, equipment, etc.) are allowed.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub allowed: HashSet<String>,
+    /// Forbidden (advantages, equipment, etc.); these win over `allowed`.
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    pub forbidden: HashSet<String>,
+}
+
+fn default_points() -> i32 { DEFAULT_POINTS }
+
+/**
+ Ways a [Ch] can break a campaign's point limits.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum BudgetError {
+    /// Spent more than the budget allows; by how much.
+    OverBudget(f64),
+    /// Took more disadvantages than the cap allows; by how much.
+    OverDisadvantageCap(f64),
 }
 
 impl Config {
+    fn with_defaults(edition: GurpsEd, tl: i32, female_as_5pts_disadvantage: bool) -> Self {
+        Self {
+            edition, tl, female_as_5pts_disadvantage,
+            genre: None,
+            points: DEFAULT_POINTS,
+            disadvantage_cap: None,
+            gender_points: vec![],
+            allowed: HashSet::new(),
+            forbidden: HashSet::new(),
+        }
+    }
+
     pub fn default_tl3(edition: GurpsEd) -> Self {
-        Self { edition, female_as_5pts_disadvantage: true, tl: 3, }
+        Self::with_defaults(edition, 3, true)
     }
 
     pub fn default_tl7(edition: GurpsEd) -> Self {
-        Self { edition, female_as_5pts_disadvantage: false, tl: 7, }
+        Self::with_defaults(edition, 7, false)
     }
 
     pub fn default_tl8(edition: GurpsEd) -> Self {
-        Self { edition, female_as_5pts_disadvantage: false, tl: 8, }
+        Self::with_defaults(edition, 8, false)
+    }
+
+    /**
+     Parse a campaign config from JSON.
+     */
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /**
+     Parse a campaign config from TOML.
+     */
+    pub fn from_toml(toml: &str) -> Result<Self, String> {
+        toml::from_str(toml).map_err(|e| e.to_string())
+    }
+
+    /**
+     Load a campaign config from file; `.toml` files are read as TOML, anything else as JSON.
+     */
+    pub fn load(filename: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(filename)
+            .map_err(|e| format!("{}: {e}", filename.display()))?;
+        match filename.extension().and_then(|x| x.to_str()) {
+            Some(x) if x.eq_ignore_ascii_case("toml") => Self::from_toml(&content),
+            _ => Self::from_json(&content)
+        }.map_err(|e| format!("{}: {e}", filename.display()))
+    }
+
+    /**
+     Set point value of `gender` for the campaign.
+     */
+    pub fn with_gender_points(mut self, gender: Gender, points: i32) -> Self {
+        self.gender_points.retain(|(g, _)| *g != gender);
+        self.gender_points.push((gender, points));
+        self
+    }
+
+    /**
+     Get point value of `gender` in the campaign &ndash; zero unless configured otherwise.
+     */
+    pub fn gender_points(&self, gender: &Gender) -> i32 {
+        match self.gender_points.iter().find(|(g, _)| g == gender) {
+            Some((_, p)) => *p,
+            None if *gender == Gender::Female && self.female_as_5pts_disadvantage => FEMALE_DISADVANTAGE,
+            None => 0
+        }
+    }
+
+    /**
+     See if the campaign allows something called `name`. Names are matched (ASCII) case-insensitively.
+     */
+    pub fn is_allowed(&self, name: &str) -> bool {
+        let name = name.trim();
+        let listed = |set: &HashSet<String>| set.iter().any(|x| x.eq_ignore_ascii_case(name));
+        !listed(&self.forbidden) && (self.allowed.is_empty() || listed(&self.allowed))
+    }
+
+    /**
+     Check `ch` against the point budget (plus whatever has been awarded since) and disadvantage cap.
+     */
+    pub fn check_budget(&self, ch: &Ch) -> Result<(), BudgetError> {
+        let gained = -ch.disadvantage_points();
+        if let Some(cap) = self.disadvantage_cap.filter(|cap| gained > *cap as f64) {
+            return Err(BudgetError::OverDisadvantageCap(gained - cap as f64));
+        }
+        let over = ch.cost() - (self.points + ch.awarded_points()) as f64;
+        if over > 0.0 {
+            return Err(BudgetError::OverBudget(over));
+        }
+        Ok(())
+    }
+}
+
+/**
+ Fetch a list of all campaign config files (".campaign.json" and ".campaign.toml").
+ */
+pub fn list_campaign_files() -> Vec<PathBuf> {
+    let mut cfs = vec![];
+    for pattern in ["./*.campaign.json", "./*.campaign.toml"] {
+        for entry in glob(pattern).expect("Failed to read glob pattern") {
+            match entry {
+                Ok(path) => cfs.push(path),
+                Err(e) => println!("{:?}", e)
+            }
+        }
+    }
+    cfs
+}
+
+#[cfg(test)]
+mod config_tests {
+    use crate::{ch::Ch, edition::GurpsEd, gender::Gender};
+
+    use super::{BudgetError, Config};
+
+    #[test]
+    fn from_json_works() {
+        let json = r#"{
+            "edition": "Ed3",
+            "tl": 3,
+            "genre": "fantasy.genre",
+            "points": 150,
+            "disadvantage_cap": 40,
+            "female_as_5pts_disadvantage": true,
+            "forbidden": ["Gunpowder"]
+        }"#;
+        let c = Config::from_json(json).unwrap();
+        assert_eq!(GurpsEd::Ed3, c.edition);
+        assert_eq!(Some("fantasy.genre".to_string()), c.genre);
+        assert_eq!(150, c.points);
+        assert_eq!(Some(40), c.disadvantage_cap);
+        assert_eq!(-5, c.gender_points(&Gender::Female));
+        assert!(!c.is_allowed("gunpowder"));
+        assert!(c.is_allowed("Broadsword"));
+        assert_eq!(c, Config::from_json(&serde_json::to_string(&c).unwrap()).unwrap());
+        assert!(Config::from_json(r#"{"tl": 3}"#).is_err());
+    }
+
+    #[test]
+    fn from_toml_works() {
+        let toml = r#"
+            edition = "Ed4"
+            tl = 8
+            allowed = ["Pistol", "Knife"]
+            gender_points = [[{ Other = "Drone" }, -10]]
+        "#;
+        let c = Config::from_toml(toml).unwrap();
+        assert_eq!(GurpsEd::Ed4, c.edition);
+        assert_eq!(100, c.points);
+        assert_eq!(None, c.genre);
+        assert!(c.is_allowed("knife"));
+        assert!(!c.is_allowed("Broadsword"));
+        assert_eq!(-10, c.gender_points(&Gender::Other("Drone".into())));
+        assert_eq!(0, c.gender_points(&Gender::Female));
+    }
+
+    #[test]
+    fn check_budget_works() {
+        let mut c = Config::default_tl8(GurpsEd::Ed4);
+        c.points = 25;
+        c.disadvantage_cap = Some(15);
+        let mut ch = Ch::new("Nameless");
+        ch.st += 3;
+        assert_eq!(Err(BudgetError::OverBudget(5.0)), c.check_budget(&ch));
+        ch.ht -= 1;
+        assert_eq!(Ok(()), c.check_budget(&ch));
+        ch.iq -= 1;
+        assert_eq!(Err(BudgetError::OverDisadvantageCap(15.0)), c.check_budget(&ch));
     }
 }
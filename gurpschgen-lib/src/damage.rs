@@ -1,11 +1,15 @@
-use std::collections::HashMap;
+pub mod st_damage;
+
+use std::{collections::HashMap, fmt::Display, iter::Sum};
 
 use serde::{Deserialize, Serialize};
 
+use crate::dice::Dice;
+
 /**
  Damage types.
  */
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize)]
 pub enum DamageType {
     /// **Cut** &ndash; sharp blades, monowire, etc.
     Cut,
@@ -20,48 +24,159 @@ pub enum DamageType {
     Spec,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+impl DamageType {
+    /// All the damage types there are.
+    pub const ALL: [DamageType; 6] = [Self::Cut, Self::Cr, Self::Energy, Self::Imp, Self::Var, Self::Spec];
+}
+
+/**
+ Combine two per-type tables with `f`.
+ */
+fn combine_per_type(a: impl Fn(DamageType) -> i32, b: impl Fn(DamageType) -> i32, f: fn(i32, i32) -> i32) -> HashMap<DamageType, i32> {
+    DamageType::ALL.iter().map(|t| (*t, f(a(*t), b(*t)))).collect()
+}
+
+/**
+ Spread `value` over all damage types, except `dtype` which gets `vs`.
+ */
+fn per_type_except(value: impl Fn(DamageType) -> i32, dtype: DamageType, vs: i32) -> HashMap<DamageType, i32> {
+    DamageType::ALL.iter().map(|t| (*t, if *t == dtype {vs} else {value(*t)})).collect()
+}
+
+/**
+ Damage resistance &ndash; either the same against everything or [DamageType] specific.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum DamageResistance {
     All(i32),
+    /// Per-type DR; types not listed get none.
     Variable(HashMap<DamageType, i32>),
 }
 
 impl DamageResistance {
+    /**
+     Get DR which applies against any and all damage &ndash; the lowest of the lot for [Variable][DamageResistance::Variable].
+     */
     pub fn value(&self) -> i32 {
         match self {
             Self::All(v) => *v,
-            _ => todo!("hashmap?!")
+            Self::Variable(_) => DamageType::ALL.iter().map(|t| self.against(*t)).min().unwrap_or(0)
+        }
+    }
+
+    /**
+     Get DR against `dtype`.
+     */
+    pub fn against(&self, dtype: DamageType) -> i32 {
+        match self {
+            Self::All(v) => *v,
+            Self::Variable(m) => *m.get(&dtype).unwrap_or(&0)
+        }
+    }
+
+    /**
+     Make DR against `dtype` to be `value`, leaving other types intact.
+     */
+    pub fn vs(self, dtype: DamageType, value: i32) -> Self {
+        Self::Variable(per_type_except(|t| self.against(t), dtype, value))
+    }
+
+    /**
+     Combine with `other` DR, e.g. of another armor layer. DR stacks, type by type.
+     */
+    pub fn combine(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Self::All(a), Self::All(b)) => Self::All(a + b),
+            _ => Self::Variable(combine_per_type(|t| self.against(t), |t| other.against(t), |a, b| a + b))
         }
     }
 }
 
+impl Default for DamageResistance {
+    fn default() -> Self {
+        Self::All(0)
+    }
+}
+
 impl From<i32> for DamageResistance {
     fn from(value: i32) -> Self {
         Self::All(value)
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+impl<'a> Sum<&'a DamageResistance> for DamageResistance {
+    fn sum<I: Iterator<Item = &'a DamageResistance>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, x| acc.combine(x))
+    }
+}
+
+/**
+ Passive defense &ndash; either the same against everything or [DamageType] specific.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum PassiveDefense {
     All(i32),
+    /// Per-type PD; types not listed get none.
     Variable(HashMap<DamageType, i32>),
 }
 
 impl PassiveDefense {
+    /**
+     Get PD which applies against any and all attacks &ndash; the lowest of the lot for [Variable][PassiveDefense::Variable].
+     */
     pub fn value(&self) -> i32 {
         match self {
             Self::All(v) => *v,
-            _ => todo!("hashmap?!")
+            Self::Variable(_) => DamageType::ALL.iter().map(|t| self.against(*t)).min().unwrap_or(0)
+        }
+    }
+
+    /**
+     Get PD against `dtype`.
+     */
+    pub fn against(&self, dtype: DamageType) -> i32 {
+        match self {
+            Self::All(v) => *v,
+            Self::Variable(m) => *m.get(&dtype).unwrap_or(&0)
+        }
+    }
+
+    /**
+     Make PD against `dtype` to be `value`, leaving other types intact.
+     */
+    pub fn vs(self, dtype: DamageType, value: i32) -> Self {
+        Self::Variable(per_type_except(|t| self.against(t), dtype, value))
+    }
+
+    /**
+     Combine with `other` PD, e.g. of another armor layer. PD does not stack &ndash; the best one counts, type by type.
+     */
+    pub fn combine(&self, other: &Self) -> Self {
+        match (self, other) {
+            (Self::All(a), Self::All(b)) => Self::All(*a.max(b)),
+            _ => Self::Variable(combine_per_type(|t| self.against(t), |t| other.against(t), i32::max))
         }
     }
 }
 
+impl Default for PassiveDefense {
+    fn default() -> Self {
+        Self::All(0)
+    }
+}
+
 impl From<i32> for PassiveDefense {
     fn from(value: i32) -> Self {
         Self::All(value)
     }
 }
 
+impl<'a> Sum<&'a PassiveDefense> for PassiveDefense {
+    fn sum<I: Iterator<Item = &'a PassiveDefense>>(iter: I) -> Self {
+        iter.fold(Self::default(), |acc, x| acc.combine(x))
+    }
+}
+
 /**
  General damage types + embedded delivery method.
  */
@@ -77,6 +192,70 @@ pub enum Damage {
     Spec(DamageDelivery),
 }
 
+impl Damage {
+    /**
+     Construct [Damage] of `dtype` delivered via `delivery`.
+     */
+    pub fn new(dtype: DamageType, delivery: DamageDelivery) -> Self {
+        match dtype {
+            DamageType::Cut => Self::Cut(delivery),
+            DamageType::Cr => Self::Cr(delivery),
+            DamageType::Energy => Self::Energy(delivery),
+            DamageType::Imp => Self::Imp(delivery),
+            DamageType::Var => Self::Var(delivery),
+            DamageType::Spec => Self::Spec(delivery),
+        }
+    }
+
+    /**
+     Get the [DamageType].
+     */
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            Self::Cut(_) => DamageType::Cut,
+            Self::Cr(_) => DamageType::Cr,
+            Self::Energy(_) => DamageType::Energy,
+            Self::Imp(_) => DamageType::Imp,
+            Self::Var(_) => DamageType::Var,
+            Self::Spec(_) => DamageType::Spec,
+        }
+    }
+
+    /**
+     Get the [DamageDelivery].
+     */
+    pub fn delivery(&self) -> &DamageDelivery {
+        match self {
+            Self::Cut(x) |
+            Self::Cr(x) |
+            Self::Energy(x) |
+            Self::Imp(x) |
+            Self::Var(x) |
+            Self::Spec(x) => x
+        }
+    }
+}
+
+impl Display for DamageType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Cut => "cut",
+            Self::Cr => "cr",
+            Self::Energy => "energy",
+            Self::Imp => "imp",
+            Self::Var => "var",
+            Self::Spec => "spec",
+        })
+    }
+}
+
+impl Display for Damage {
+    /// E.g. "2d-1 cut".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.delivery(), self.damage_type())
+    }
+}
+
 /**
  Some common damage delivery methods.
  */
@@ -98,3 +277,88 @@ pub enum DamageDelivery {
     /// **Spec**ial damage, see your games' rules for details.
     Spec(i32),
 }
+
+impl DamageDelivery {
+    /**
+     Get the delivery as [Dice], if it's dice based (or flat).
+     */
+    pub fn dice(&self) -> Option<Dice> {
+        match self {
+            Self::Dice(d, a) => Some(Dice::new(*d, *a)),
+            Self::DiceMul(d, a, m) => Some(Dice::new(*d, *a).with_mul(*m)),
+            Self::Flat(x) => Some(Dice::new(0, *x)),
+            _ => None
+        }
+    }
+
+    /**
+     Get the delivery at half damage, e.g. past a ranged weapon's half damage range.
+
+     Dice get a ×½ multiplier, flat damage is halved (rounding down). Others aren't affected.
+     */
+    pub fn halved(&self) -> Self {
+        match self {
+            Self::Dice(d, a) => Self::DiceMul(*d, *a, 0.5),
+            Self::DiceMul(d, a, m) => Self::DiceMul(*d, *a, m * 0.5),
+            Self::Flat(x) => Self::Flat(x / 2),
+            x => x.clone()
+        }
+    }
+}
+
+/**
+ Write `adds` the GURPS way &ndash; signed, and nothing at all if zero.
+ */
+fn fmt_adds(adds: i32) -> String {
+    if adds == 0 { String::new() } else { format!("{adds:+}") }
+}
+
+impl Display for DamageDelivery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dice(..) |
+            Self::DiceMul(..) => write!(f, "{}", self.dice().unwrap()),
+            Self::Flat(x) => write!(f, "{x}"),
+            Self::Sw(a) => write!(f, "sw{}", fmt_adds(*a)),
+            Self::Thr(a) => write!(f, "thr{}", fmt_adds(*a)),
+            Self::Var => write!(f, "var"),
+            Self::Spec(x) => write!(f, "spec/{x}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod damage_tests {
+    use super::{Damage, DamageDelivery, DamageResistance, DamageType, PassiveDefense};
+
+    #[test]
+    fn display_works() {
+        assert_eq!("2d-1 cut", Damage::Cut(DamageDelivery::Dice(2, -1)).to_string());
+        assert_eq!("sw+1 cr", Damage::Cr(DamageDelivery::Sw(1)).to_string());
+        assert_eq!("3d+2x1.5 imp", Damage::Imp(DamageDelivery::DiceMul(3, 2, 1.5)).to_string());
+    }
+
+    #[test]
+    fn typed_dr_works() {
+        let dr = DamageResistance::from(4).vs(DamageType::Imp, 2);
+        assert_eq!(4, dr.against(DamageType::Cut));
+        assert_eq!(2, dr.against(DamageType::Imp));
+        assert_eq!(2, dr.value());
+    }
+
+    #[test]
+    fn dr_stacks() {
+        let dr: DamageResistance = [DamageResistance::from(2), DamageResistance::from(3)].iter().sum();
+        assert_eq!(DamageResistance::All(5), dr);
+        let dr = dr.combine(&DamageResistance::from(1).vs(DamageType::Cr, 0));
+        assert_eq!(6, dr.against(DamageType::Imp));
+        assert_eq!(5, dr.against(DamageType::Cr));
+    }
+
+    #[test]
+    fn pd_does_not_stack() {
+        let pd: PassiveDefense = [PassiveDefense::from(2), PassiveDefense::from(1).vs(DamageType::Imp, 3)].iter().sum();
+        assert_eq!(2, pd.against(DamageType::Cr));
+        assert_eq!(3, pd.against(DamageType::Imp));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::edition::GurpsEd;
+
+use super::DamageDelivery;
+
+/**
+ 4e thrust/swing above ST 40, in 5 ST steps from ST 45 on, as "steps" (see [from_steps]).
+ */
+const ED4_HIGH_ST: [(i32, i32); 12] = [
+    (20, 29), (22, 31), (24, 33), (27, 36), (29, 38), (32, 40),
+    (34, 42), (36, 44), (38, 46), (40, 48), (42, 50), (44, 52),
+];
+
+/**
+ Convert damage "steps" into dice + adds.
+
+ A step is a single add; four of them make a die, e.g. 1d+2 → 2d-1 → 2d → 2d+1.
+ Adds are kept within -1..+2 whenever there's more than one die.
+ */
+pub fn from_steps(steps: i32) -> DamageDelivery {
+    let dice = ((steps + 1).div_euclid(4)).max(1);
+    DamageDelivery::Dice(dice, steps - 4 * dice)
+}
+
+/**
+ Convert dice + adds into "steps", see [from_steps].
+ */
+pub fn to_steps(dice: i32, adds: i32) -> i32 {
+    4 * dice + adds
+}
+
+fn thrust_steps(st: i32, edition: &GurpsEd) -> i32 {
+    match edition {
+        GurpsEd::Ed3 => if st <= 6 { st - 6 } else { 4 + (st - 13).div_euclid(2) },
+        GurpsEd::Ed4 => match st {
+            ..=40 => 4 + (st - 13).div_euclid(2),
+            41..=44 => thrust_steps(40, edition),
+            45..=100 => ED4_HIGH_ST[((st - 45) / 5) as usize].0,
+            _ => ED4_HIGH_ST[11].0 + 4 * ((st - 100) / 10)
+        }
+    }
+}
+
+fn swing_steps(st: i32, edition: &GurpsEd) -> i32 {
+    match edition {
+        GurpsEd::Ed3 => st - 6,
+        GurpsEd::Ed4 => match st {
+            ..=8 => thrust_steps(st, edition) + 1,
+            9..=26 => 7 + (st - 13),
+            27..=44 => 21 + (st.min(40) - 27).div_euclid(2),
+            45..=100 => ED4_HIGH_ST[((st - 45) / 5) as usize].1,
+            _ => ED4_HIGH_ST[11].1 + 4 * ((st - 100) / 10)
+        }
+    }
+}
+
+/**
+ Get basic thrust damage for `st`, with modifier `adds` (e.g. from a weapon) applied.
+ */
+pub fn thrust(st: i32, adds: i32, edition: &GurpsEd) -> DamageDelivery {
+    from_steps(thrust_steps(st, edition) + adds)
+}
+
+/**
+ Get basic swing damage for `st`, with modifier `adds` (e.g. from a weapon) applied.
+ */
+pub fn swing(st: i32, adds: i32, edition: &GurpsEd) -> DamageDelivery {
+    from_steps(swing_steps(st, edition) + adds)
+}
+
+#[cfg(test)]
+mod st_damage_tests {
+    use crate::{damage::DamageDelivery, edition::GurpsEd};
+
+    use super::{swing, thrust};
+
+    #[test]
+    fn ed4_table_works() {
+        let ed = GurpsEd::Ed4;
+        assert_eq!(DamageDelivery::Dice(1, -6), thrust(1, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(1, -2), swing(8, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(1, -2), thrust(10, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(1, 0), swing(10, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(1, 2), swing(12, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(5, 0), swing(26, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(5, 1), swing(28, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(7, -1), swing(40, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(4, 1), thrust(40, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(11, 0), thrust(100, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(14, 0), swing(110, 0, &ed));
+    }
+
+    #[test]
+    fn ed3_table_works() {
+        let ed = GurpsEd::Ed3;
+        assert_eq!(DamageDelivery::Dice(1, -5), thrust(5, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(1, -3), thrust(8, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(1, -2), swing(8, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(6, -1), swing(29, 0, &ed));
+        assert_eq!(DamageDelivery::Dice(8, 2), swing(40, 0, &ed));
+    }
+
+    #[test]
+    fn adds_roll_over() {
+        assert_eq!(DamageDelivery::Dice(2, -1), swing(12, 1, &GurpsEd::Ed4));
+        assert_eq!(DamageDelivery::Dice(1, 2), swing(13, -1, &GurpsEd::Ed3));
+    }
+}
@@ -0,0 +1,263 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/**
+ Anything which can roll a die.
+ */
+pub trait DieRoller {
+    /**
+     Roll a single `sides` sided die.
+
+     **Returns** a value within `1..=sides`.
+     */
+    fn roll_die(&mut self, sides: i32) -> i32;
+}
+
+/**
+ A small, seedable pseudo-random die roller (SplitMix64).
+
+ Same seed, same rolls &ndash; handy for tests and for re-generating things.
+ */
+#[derive(Debug, Clone)]
+pub struct SeededRoller {
+    state: u64,
+}
+
+impl SeededRoller {
+    /**
+     Instantiate a roller with a given `seed`.
+     */
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /**
+     Instantiate a roller seeded from the system clock.
+     */
+    pub fn from_clock() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::new(nanos)
+    }
+
+    /**
+     Get the next raw pseudo-random value.
+     */
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl DieRoller for SeededRoller {
+    fn roll_die(&mut self, sides: i32) -> i32 {
+        let sides = sides.max(1) as u64;
+        (self.next_u64() % sides) as i32 + 1
+    }
+}
+
+/**
+ A dice expression, e.g. `3d-1`, `2d+2x1.5` or `1d8`.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Dice {
+    pub count: i32,
+    pub sides: i32,
+    pub adds: i32,
+    /// Multiplier for the end result, if any.
+    pub mul: Option<f64>,
+}
+
+impl Dice {
+    /**
+     Instantiate `count`d6 with `adds`.
+     */
+    pub fn new(count: i32, adds: i32) -> Self {
+        Self { count, sides: 6, adds, mul: None }
+    }
+
+    /**
+     Set multiplier.
+     */
+    pub fn with_mul(mut self, mul: f64) -> Self {
+        self.mul = Some(mul);
+        self
+    }
+
+    fn apply_mul(&self, value: i32) -> f64 {
+        value as f64 * self.mul.unwrap_or(1.0)
+    }
+
+    /**
+     Roll the dice with `roller`. Multiplied results are rounded down.
+     */
+    pub fn roll(&self, roller: &mut impl DieRoller) -> i32 {
+        let rolled: i32 = (0..self.count).map(|_| roller.roll_die(self.sides)).sum();
+        self.apply_mul(rolled + self.adds).floor() as i32
+    }
+
+    /**
+     Get the lowest possible result.
+     */
+    pub fn min(&self) -> f64 {
+        self.apply_mul(self.count + self.adds)
+    }
+
+    /**
+     Get the highest possible result.
+     */
+    pub fn max(&self) -> f64 {
+        self.apply_mul(self.count * self.sides + self.adds)
+    }
+
+    /**
+     Get the average result.
+     */
+    pub fn mean(&self) -> f64 {
+        (self.count as f64 * (self.sides + 1) as f64 / 2.0 + self.adds as f64) * self.mul.unwrap_or(1.0)
+    }
+
+    /**
+     Get the probability distribution of the sum of dice + adds (multiplier not applied).
+
+     **Returns** `(result, probability)` pairs, from lowest to highest result.
+     */
+    pub fn distribution(&self) -> Vec<(i32, f64)> {
+        let mut dist = vec![1.0];
+        for _ in 0..self.count {
+            let mut next = vec![0.0; dist.len() + self.sides as usize - 1];
+            for (i, p) in dist.iter().enumerate() {
+                for face in 0..self.sides as usize {
+                    next[i + face] += p / self.sides as f64;
+                }
+            }
+            dist = next;
+        }
+        dist.into_iter().enumerate()
+            .map(|(i, p)| (i as i32 + self.count + self.adds, p))
+            .collect()
+    }
+}
+
+/**
+ Get the probability of succeeding a 3d6 roll against `skill`.
+
+ 3 and 4 always succeed, 17 and 18 always fail.
+ */
+pub fn success_chance(skill: i32) -> f64 {
+    Dice::new(3, 0).distribution().iter()
+        .filter(|(r, _)| *r <= 4 || (*r <= 16 && *r <= skill))
+        .map(|(_, p)| p)
+        .sum()
+}
+
+impl Display for Dice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}d", self.count)?;
+        if self.sides != 6 {
+            write!(f, "{}", self.sides)?;
+        }
+        if self.adds != 0 {
+            write!(f, "{:+}", self.adds)?;
+        }
+        if let Some(m) = self.mul {
+            write!(f, "x{m}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Dice {
+    type Err = String;
+
+    /**
+     Parse GURPS dice notation: `3d-1`, `2d+2x1.5`, `2d+2(x1.5)`, `1d6`, `3d6+2`.
+     */
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("malformed dice expression {s:?}");
+        let t = s.trim();
+        let (t, mul) = match t.find(['x', 'X', '×']) {
+            Some(i) => {
+                let m = t[i..].trim_start_matches(['x', 'X', '×']).trim_end_matches(')').trim();
+                let m = m.parse::<f64>().map_err(|_| err())?;
+                (t[..i].trim_end_matches('(').trim(), Some(m))
+            },
+            None => (t, None)
+        };
+        let (count, rest) = t.split_once(['d', 'D']).ok_or_else(err)?;
+        let count = match count.trim() {
+            "" => 1,
+            c => c.parse::<i32>().map_err(|_| err())?
+        };
+        let (sides, adds) = match rest.find(['+', '-']) {
+            Some(i) => (&rest[..i], rest[i..].trim()),
+            None => (rest, "")
+        };
+        let sides = match sides.trim() {
+            "" => 6,
+            x => x.parse::<i32>().map_err(|_| err())?
+        };
+        let adds = match adds {
+            "" => 0,
+            a => a.parse::<i32>().map_err(|_| err())?
+        };
+        Ok(Self { count, sides, adds, mul })
+    }
+}
+
+#[cfg(test)]
+mod dice_tests {
+    use super::{success_chance, Dice, DieRoller, SeededRoller};
+
+    struct Fixed(Vec<i32>);
+
+    impl DieRoller for Fixed {
+        fn roll_die(&mut self, _: i32) -> i32 {
+            self.0.remove(0)
+        }
+    }
+
+    #[test]
+    fn parsing_and_printing_works() {
+        for s in ["3d-1", "2d+2x1.5", "1d6", "1d8+1", "4d"] {
+            let d = s.parse::<Dice>().unwrap();
+            let expected = if s == "1d6" { "1d" } else { s };
+            assert_eq!(expected, d.to_string());
+        }
+        assert_eq!(Dice::new(3, 2).with_mul(1.5), "3d+2(X1.5)".parse().unwrap());
+        assert!("bogus".parse::<Dice>().is_err());
+    }
+
+    #[test]
+    fn rolling_works() {
+        assert_eq!(9, Dice::new(2, -1).roll(&mut Fixed(vec![4, 6])));
+        assert_eq!(13, Dice::new(2, 0).with_mul(1.5).roll(&mut Fixed(vec![4, 5])));
+        let mut a = SeededRoller::new(42);
+        let mut b = SeededRoller::new(42);
+        let d = Dice::new(3, 0);
+        for _ in 0..100 {
+            let r = d.roll(&mut a);
+            assert!((3..=18).contains(&r));
+            assert_eq!(r, d.roll(&mut b));
+        }
+    }
+
+    #[test]
+    fn statistics_work() {
+        let d = Dice::new(3, -1);
+        assert_eq!(2.0, d.min());
+        assert_eq!(17.0, d.max());
+        assert_eq!(9.5, d.mean());
+        let total: f64 = d.distribution().iter().map(|(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!((success_chance(10) - 0.5).abs() < 1e-9);
+        assert!((success_chance(3) - 4.0 / 216.0).abs() < 1e-9);
+        assert!((success_chance(20) - 212.0 / 216.0).abs() < 1e-9);
+    }
+}
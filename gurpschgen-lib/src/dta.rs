@@ -1,3 +1,5 @@
 pub mod locate_dta;
 pub mod read_lines;
 pub mod genre;
+pub mod meta;
+pub mod encoding;
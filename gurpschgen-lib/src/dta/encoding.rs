@@ -0,0 +1,127 @@
+/**
+ Character encodings commonly found in (old) DTA/GEN files.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    /// MS-DOS "US" codepage &ndash; what MakeChar itself used.
+    Cp437,
+    /// MS-DOS "Western European" codepage.
+    Cp850,
+    /// ISO-8859-1.
+    Latin1,
+}
+
+/// CP437 bytes 0x80‥0xFF.
+const CP437_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒáíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}";
+/// CP850 bytes 0x80‥0xFF.
+const CP850_HIGH: &str = "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜø£Ø×ƒáíóúñÑªº¿®¬½¼¡«»░▒▓│┤ÁÂÀ©╣║╗╝¢¥┐└┴┬├─┼ãÃ╚╔╩╦╠═╬¤ðÐÊËÈıÍÎÏ┘┌█▄¦Ì▀ÓßÔÒõÕµþÞÚÛÙýÝ¯´\u{ad}±‗¾¶§÷¸°¨·¹³²■\u{a0}";
+
+/// Legacy encodings in order of preference when guessing ends in a tie.
+const LEGACY: [Encoding; 3] = [Encoding::Cp437, Encoding::Cp850, Encoding::Latin1];
+
+impl Encoding {
+    /**
+     Decode `bytes` into a [String].
+
+     Invalid UTF-8 is replaced with U+FFFD; the single-byte codepages can't fail.
+     */
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).to_string(),
+            Self::Latin1 => bytes.iter().map(|b| *b as char).collect(),
+            Self::Cp437 => decode_with(CP437_HIGH, bytes),
+            Self::Cp850 => decode_with(CP850_HIGH, bytes),
+        }
+    }
+
+    /**
+     Guess the encoding of `bytes`.
+
+     Valid UTF-8 (including plain ASCII) is taken as such. A truncated multibyte sequence at the
+     very end is allowed, as `bytes` might be just a sniffed chunk of a larger file.
+     Otherwise the legacy codepage which makes the most "word-like" sense of the high bytes wins.
+     */
+    pub fn guess(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(_) => return Self::Utf8,
+            Err(e) if e.error_len().is_none() && e.valid_up_to() > 0 => return Self::Utf8,
+            _ => ()
+        }
+        Self::guess_legacy(bytes)
+    }
+
+    /**
+     Guess which of the legacy (single-byte) codepages `bytes` are in.
+     */
+    pub fn guess_legacy(bytes: &[u8]) -> Self {
+        let mut best = (Self::Cp437, i32::MIN);
+        for enc in LEGACY {
+            let score = enc.score(bytes);
+            if score > best.1 {
+                best = (enc, score)
+            }
+        }
+        best.0
+    }
+
+    /**
+     Score how plausible the decoding of `bytes` is: latin letters are good, more so if their case
+     agrees with neighboring ASCII letters; control and box-drawing characters are bad.
+     */
+    fn score(&self, bytes: &[u8]) -> i32 {
+        let mut score = 0;
+        for (i, b) in bytes.iter().enumerate() {
+            if *b < 0x80 { continue; }
+            let c = self.decode(&[*b]).chars().next().unwrap_or('\u{fffd}');
+            if c.is_control() || ('\u{2500}'..='\u{259f}').contains(&c) {
+                score -= 1;
+            } else if c.is_alphabetic() && c <= '\u{24f}' {
+                score += 1;
+                let neighbor = [i.checked_sub(1), i.checked_add(1)].into_iter()
+                    .flatten()
+                    .filter_map(|n| bytes.get(n))
+                    .find(|n| n.is_ascii_alphabetic());
+                if let Some(n) = neighbor {
+                    if n.is_ascii_lowercase() == c.is_lowercase() {
+                        score += 1
+                    }
+                }
+            }
+        }
+        score
+    }
+}
+
+fn decode_with(table: &str, bytes: &[u8]) -> String {
+    bytes.iter().map(|b| match b {
+        0x00..=0x7f => *b as char,
+        _ => table.chars().nth((*b - 0x80) as usize).unwrap_or('\u{fffd}')
+    }).collect()
+}
+
+#[cfg(test)]
+mod encoding_tests {
+    use super::{Encoding, CP437_HIGH, CP850_HIGH};
+
+    #[test]
+    fn tables_are_complete() {
+        assert_eq!(128, CP437_HIGH.chars().count());
+        assert_eq!(128, CP850_HIGH.chars().count());
+    }
+
+    #[test]
+    fn cp437_decoding_works() {
+        assert_eq!("Zoë ░", Encoding::Cp437.decode(b"Zo\x89 \xb0"));
+    }
+
+    #[test]
+    fn guessing_works() {
+        assert_eq!(Encoding::Utf8, Encoding::guess("Zoë".as_bytes()));
+        assert_eq!(Encoding::Utf8, Encoding::guess(b"plain ascii"));
+        // ë as CP437/850...
+        assert_eq!(Encoding::Cp437, Encoding::guess(b"Zo\x89 and Fran\x87ois"));
+        // ...and as Latin-1.
+        assert_eq!(Encoding::Latin1, Encoding::guess(b"Zo\xeb and caf\xe9"));
+    }
+}
@@ -3,7 +3,9 @@ use std::{collections::HashMap, path::PathBuf};
 use glob::glob;
 use serde::{Deserialize, Serialize};
 
-use crate::{context::{Context, ContextPayload}, misc::tl::TL};
+use crate::{context::{Context, ContextPayload}, equipment::Equipment, misc::{category::CategoryPayload, tl::TL}, modifier::effect::EffectModifier};
+
+use super::meta::DtaFile;
 
 /**
  Genre data goes here.
@@ -56,6 +58,46 @@ impl Genre {
         }
     }
 
+    /**
+     Find an item by `name` within `context`, whichever category it happens to be in.
+
+     Exact name match is preferred, but (ASCII) case-insensitive match will do.
+     */
+    pub fn find(&self, context: &Context, name: &str) -> Option<&CategoryPayload> {
+        let name = name.trim();
+        let cats = &self.items.get(context)?.items;
+        cats.values().find_map(|cat| cat.items.get(name))
+            .or_else(|| cats.values().find_map(|cat| cat.items.iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, p)| p)))
+    }
+
+    /**
+     Find an [Equipment] by `name`.
+     */
+    pub fn find_equipment(&self, name: &str) -> Option<&Equipment> {
+        match self.find(&Context::Equipment, name) {
+            Some(CategoryPayload::Equipment(e)) => Some(e),
+            _ => None
+        }
+    }
+
+    /**
+     Find a modifier called `name` within mod group `group`.
+
+     Names are matched the same way as in [Genre::find].
+     */
+    pub fn find_modifier(&self, group: &str, name: &str) -> Option<&EffectModifier> {
+        let name = name.trim();
+        let cat = self.items.get(&Context::Modifier)?.items.get(group.trim())?;
+        cat.items.get(name)
+            .or_else(|| cat.items.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, p)| p))
+            .and_then(|p| match p {
+                CategoryPayload::Modifier(m) => Some(m),
+                _ => None
+            })
+    }
+
     /**
      Load a genre from file.
      */
@@ -65,13 +107,13 @@ impl Genre {
         ).expect("Error in JSON!");
         for f in &genre.files {
             let json = std::fs::read_to_string(f).expect(format!("Fail with {f}").as_str());
-            let loaded_map: HashMap<Context, ContextPayload> = serde_json::from_str(&json).expect("Error in JSON!");
+            let loaded: DtaFile = serde_json::from_str(&json).expect("Error in JSON!");
             // As simple .extend() doesn't suffice(?), we have to travel through the whole thing...
-            for loaded_ct in loaded_map {
+            for loaded_ct in loaded.items {
                 if let Some(context_payload) = genre.items.get_mut(&loaded_ct.0) {
                     for loaded_ctg in loaded_ct.1.items {
                         if let Some(cat) = context_payload.items.get_mut(&loaded_ctg.0) {
-                            cat.items.extend(loaded_ctg.1.items);
+                            cat.merge(loaded_ctg.1);
                         } else {
                             context_payload.items.insert(loaded_ctg.0.to_string(), loaded_ctg.1.clone());
                         }
@@ -6,6 +6,17 @@ use std::{env, path::Path};
  **Panics** if suitable directory not found.
  */
 pub fn locate_dta(verbose: bool) {
+    if !try_locate_dta(verbose) {
+        panic!("We could not locate .dta/.gen (or other such) file(s) in any (internally) specified potential locations!")
+    }
+}
+
+/**
+ Like [locate_dta], but for when the datafiles are merely nice to have.
+
+ **Returns** `true` if found (and moved into), `false` otherwise.
+ */
+pub fn try_locate_dta(verbose: bool) -> bool {
     // where the datafiles might be lurking?
     let possible_dta_location = [
         "./datafiles",
@@ -19,19 +30,14 @@ pub fn locate_dta(verbose: bool) {
         "../dta"
     ];
 
-    let mut found_dtas = false;
     // Scan around - break early.
     for path in possible_dta_location {
-        if env::set_current_dir(&Path::new(path)).is_ok() {
+        if env::set_current_dir(Path::new(path)).is_ok() {
             // Found! We want to live there, too.
             let cwd = env::current_dir().unwrap();
             if verbose {println!("DTA found in {}", cwd.display());}
-            found_dtas = true;
-            break;
+            return true;
         }
     }
-
-    if !found_dtas {
-        panic!("We could not locate .dta/.gen (or other such) file(s) in any (internally) specified potential locations!")
-    }
+    false
 }
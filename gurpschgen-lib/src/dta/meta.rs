@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::context::{Context, ContextPayload};
+
+/**
+ File-level metadata of a converted DTA/GEN file &ndash; credits and such.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct DtaMeta {
+    /// Title of the data file, if it had one.
+    pub title: Option<String>,
+    /// Author(s) of the data file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authors: Vec<String>,
+    /// Name of the original DTA/GEN file.
+    pub source: Option<String>,
+    /// Date of conversion, `YYYY-MM-DD`.
+    pub converted: Option<String>,
+    /// Converter name and version.
+    pub converter: Option<String>,
+    /// File-level comments (those before the first "type").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/**
+ A converted DTA/GEN file: [metadata][DtaMeta] + the [Context] → [ContextPayload] tree.
+
+ *dev NOTE:* the tree is flattened so that files without `meta` (older conversions) load just fine.
+ */
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct DtaFile {
+    #[serde(default)]
+    pub meta: DtaMeta,
+    #[serde(flatten)]
+    pub items: HashMap<Context, ContextPayload>,
+}
+
+#[cfg(test)]
+mod meta_tests {
+    use std::collections::{HashMap, HashSet};
+
+    use crate::{context::{Context, ContextPayload}, damage::{Damage, DamageDelivery}, dta::genre::Genre, equipment::Equipment, inventory::Possession, misc::category::CategoryPayload, modifier::effect::{Effect, Op, Target}};
+
+    use super::{DtaFile, DtaMeta};
+
+    /**
+     Load one of the shipped datafiles.
+     */
+    fn shipped(filename: &str) -> DtaFile {
+        let path = format!("{}/../dta2json/datafiles/{filename}", env!("CARGO_MANIFEST_DIR"));
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn meta_roundtrip_works() {
+        let mut items = HashMap::new();
+        items.insert(Context::Quirk, ContextPayload::new(Context::Quirk));
+        let f = DtaFile {
+            meta: DtaMeta {
+                title: Some("GURPS Test data".to_string()),
+                authors: vec!["Somebody".to_string()],
+                ..Default::default()
+            },
+            items,
+        };
+        let json = serde_json::to_string(&f).unwrap();
+        let f: DtaFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(Some("GURPS Test data".to_string()), f.meta.title);
+        assert!(f.items.contains_key(&Context::Quirk));
+    }
+
+    #[test]
+    fn metaless_json_works() {
+        let json = r#"{"Quirk":{"context":"Quirk","items":{}}}"#;
+        let f: DtaFile = serde_json::from_str(json).unwrap();
+        assert_eq!(DtaMeta::default(), f.meta);
+        assert!(f.items.contains_key(&Context::Quirk));
+    }
+
+    #[test]
+    fn shipped_armor_cover_works() {
+        let f = shipped("tl08-equipment.json");
+        let Some(CategoryPayload::Equipment(Equipment::Armor(a))) = f.items[&Context::Equipment].items["Armor (TL8)"].items.get("Heavy Monocrys Vest") else {
+            panic!("No Heavy Monocrys Vest?!")
+        };
+        assert_eq!(&HashSet::from([9, 10, 11, 17, 18]), a.cover());
+        let Some(CategoryPayload::Equipment(Equipment::Armor(a))) = f.items[&Context::Equipment].items["Armor (TL8)"].items.get("Heavy Monocrys Suit") else {
+            panic!("No Heavy Monocrys Suit?!")
+        };
+        assert_eq!(&HashSet::from_iter(6..=18), a.cover());
+    }
+
+    #[test]
+    fn shipped_modifier_effects_work() {
+        let f = shipped("tl08-equipment.json");
+        let Some(CategoryPayload::Modifier(m)) = f.items[&Context::Modifier].items["Sword Quality"].items.get("Fine") else {
+            panic!("No Fine?!")
+        };
+        assert_eq!(vec![
+            Effect { op: Op::Mul(4.0), target: Target::Cost },
+            Effect { op: Op::Add(1.0), target: Target::Damage },
+        ], m.effects);
+        let f = shipped("basic.json");
+        let Some(CategoryPayload::Modifier(m)) = f.items[&Context::Modifier].items["Frequency Modifiers"].items.get("<= 12") else {
+            panic!("No <= 12?!")
+        };
+        assert_eq!(vec![Effect { op: Op::Mul(2.0), target: Target::PointCost }], m.effects);
+    }
+
+    #[test]
+    fn shipped_quality_damage_works() {
+        let dir = format!("{}/../dta2json/datafiles", env!("CARGO_MANIFEST_DIR"));
+        let genre = Genre { files: vec![format!("{dir}/martial-arts.json"), format!("{dir}/tl08-equipment.json")], ..Genre::new() };
+        let filename = std::env::temp_dir().join("gurpschgen-quality-test.genre");
+        std::fs::write(&filename, serde_json::to_string(&genre).unwrap()).unwrap();
+        let genre = Genre::load(&filename);
+        std::fs::remove_file(&filename).ok();
+
+        let mut katana = Possession::new(genre.find_equipment("Katana").unwrap().clone(), 1);
+        assert!(katana.select_modifier(&genre, "Sword Quality", "Fine"));
+        assert_eq!(vec![Damage::Cut(DamageDelivery::Sw(3)), Damage::Imp(DamageDelivery::Thr(3))], katana.damage());
+    }
+}
@@ -1,5 +1,10 @@
 use std::{fs::File, io::{BufRead, BufReader, Lines, Result}, path::Path};
 
+use super::encoding::Encoding;
+
+/// MS-DOS end-of-file marker, ^Z.
+const DOS_EOF: u8 = 0x1a;
+
 /**
  Read lines from `filename`.
 
@@ -10,3 +15,166 @@ where P: AsRef<Path>, {
     let file = File::open(filename)?;
     Ok(BufReader::new(file).lines())
 }
+
+/**
+ Open `filename` for [DtaReader]-style reading.
+
+ **Returns** `Ok()` or `Err()`.
+ */
+pub fn read_dta<P>(filename: P) -> Result<DtaReader<BufReader<File>>>
+where P: AsRef<Path>, {
+    let file = File::open(filename)?;
+    Ok(DtaReader::new(BufReader::new(file)))
+}
+
+/**
+ A single (decoded) line of a DTA/GEN file.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DtaLine {
+    /// Physical (1-based) line number in the file.
+    pub number: usize,
+    pub text: String,
+}
+
+/**
+ Streaming DTA/GEN line reader.
+
+ * Encoding is sniffed from the start of the stream (see [Encoding::guess]) unless given explicitly;
+   a stray non-UTF-8 line in an otherwise UTF-8 file is decoded with a best-guess legacy codepage.
+ * Both LF and CRLF line endings are fine, and a leading UTF-8 BOM is dropped.
+ * DOS EOF (0x1A) ends the file, just like it did under DOS.
+ */
+pub struct DtaReader<R: BufRead> {
+    reader: R,
+    encoding: Option<Encoding>,
+    line: usize,
+    eof: bool,
+}
+
+impl<R: BufRead> DtaReader<R> {
+    /**
+     Instantiate a new [DtaReader], sniffing the encoding upon first read.
+     */
+    pub fn new(reader: R) -> Self {
+        Self { reader, encoding: None, line: 0, eof: false }
+    }
+
+    /**
+     Instantiate a new [DtaReader] with a known `encoding`.
+     */
+    pub fn with_encoding(reader: R, encoding: Encoding) -> Self {
+        Self { reader, encoding: Some(encoding), line: 0, eof: false }
+    }
+
+    /**
+     Get the encoding in use &ndash; `None` until the first line has been read, unless given explicitly.
+     */
+    pub fn encoding(&self) -> Option<Encoding> {
+        self.encoding
+    }
+
+    fn sniff(&mut self) -> Result<Encoding> {
+        let buf = self.reader.fill_buf()?;
+        let bom = buf.starts_with(b"\xef\xbb\xbf");
+        let end = buf.iter().position(|b| *b == DOS_EOF).unwrap_or(buf.len());
+        let encoding = Encoding::guess(&buf[..end]);
+        if bom {
+            self.reader.consume(3);
+        }
+        Ok(encoding)
+    }
+}
+
+impl<R: BufRead> Iterator for DtaReader<R> {
+    type Item = Result<DtaLine>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.eof {
+            return None;
+        }
+
+        let encoding = match self.encoding {
+            Some(e) => e,
+            None => match self.sniff() {
+                Ok(e) => { self.encoding = Some(e); e },
+                Err(e) => { self.eof = true; return Some(Err(e)) }
+            }
+        };
+
+        let mut buf = vec![];
+        match self.reader.read_until(b'\n', &mut buf) {
+            Ok(0) => { self.eof = true; return None },
+            Err(e) => { self.eof = true; return Some(Err(e)) },
+            Ok(_) => ()
+        }
+        self.line += 1;
+
+        if let Some(pos) = buf.iter().position(|b| *b == DOS_EOF) {
+            buf.truncate(pos);
+            self.eof = true;
+            if buf.is_empty() {
+                return None;
+            }
+        }
+        if buf.ends_with(b"\n") { buf.pop(); }
+        if buf.ends_with(b"\r") { buf.pop(); }
+
+        let text = match (encoding, String::from_utf8(buf)) {
+            (_, Ok(s)) if encoding == Encoding::Utf8 || s.is_ascii() => s,
+            (Encoding::Utf8, Err(e)) => {
+                let bytes = e.into_bytes();
+                Encoding::guess_legacy(&bytes).decode(&bytes)
+            },
+            (_, Ok(s)) => encoding.decode(s.as_bytes()),
+            (_, Err(e)) => encoding.decode(&e.into_bytes()),
+        };
+
+        Some(Ok(DtaLine { number: self.line, text }))
+    }
+}
+
+#[cfg(test)]
+mod read_lines_tests {
+    use std::io::Cursor;
+
+    use crate::dta::encoding::Encoding;
+
+    use super::{DtaLine, DtaReader};
+
+    fn lines(raw: &[u8]) -> Vec<DtaLine> {
+        DtaReader::new(Cursor::new(raw.to_vec())).map(|l| l.unwrap()).collect()
+    }
+
+    #[test]
+    fn crlf_and_numbering_works() {
+        let ls = lines(b"first\r\nsecond\r\n\r\nfourth");
+        assert_eq!(4, ls.len());
+        assert_eq!("second", ls[1].text);
+        assert_eq!(DtaLine { number: 4, text: "fourth".to_string() }, ls[3]);
+    }
+
+    #[test]
+    fn dos_eof_ends_it() {
+        let ls = lines(b"first\r\nsecond\r\n\x1a\x00\x00garbage\r\n");
+        assert_eq!(2, ls.len());
+        let ls = lines(b"first\r\nsecond\x1a");
+        assert_eq!("second", ls[1].text);
+    }
+
+    #[test]
+    fn cp437_is_detected() {
+        let mut r = DtaReader::new(Cursor::new(b"title Fran\x87ois' data\r\n  Zo\x89; 5/0\r\n".to_vec()));
+        let l = r.next().unwrap().unwrap();
+        assert_eq!(Some(Encoding::Cp437), r.encoding());
+        assert_eq!("title François' data", l.text);
+        assert_eq!("  Zoë; 5/0", r.next().unwrap().unwrap().text);
+    }
+
+    #[test]
+    fn bom_is_dropped() {
+        let ls = lines("\u{feff}GURPS data file\nZoë\n".as_bytes());
+        assert_eq!("GURPS data file", ls[0].text);
+        assert_eq!("Zoë", ls[1].text);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::edition::GurpsEd;
+
+/**
+ Encumbrance levels.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Encumbrance {
+    None,
+    Light,
+    Medium,
+    Heavy,
+    ExtraHeavy,
+    /// Too much to carry at all.
+    Overloaded,
+}
+
+/// Upper weight limits of each level, as multiples of Basic Lift (4e) &ndash; or of 2×ST (3e).
+const LIMITS: [(Encumbrance, f64); 5] = [
+    (Encumbrance::None, 1.0),
+    (Encumbrance::Light, 2.0),
+    (Encumbrance::Medium, 3.0),
+    (Encumbrance::Heavy, 6.0),
+    (Encumbrance::ExtraHeavy, 10.0),
+];
+
+/**
+ Get Basic Lift (lbs) for `st`. 3e has no such thing, but "no encumbrance" tops at 2×ST, which serves the same purpose.
+ */
+pub fn basic_lift(st: i32, edition: &GurpsEd) -> f64 {
+    match edition {
+        GurpsEd::Ed3 => 2.0 * st as f64,
+        GurpsEd::Ed4 => {
+            let bl = (st * st) as f64 / 5.0;
+            // BL of 10+ lbs is rounded to nearest whole lb.
+            if bl >= 10.0 { bl.round() } else { bl }
+        }
+    }
+}
+
+impl Encumbrance {
+    /**
+     Determine encumbrance level for carrying `weight` lbs with given `basic_lift`.
+     */
+    pub fn from_weight(weight: f64, basic_lift: f64) -> Self {
+        LIMITS.iter()
+            .find(|(_, x)| weight <= x * basic_lift)
+            .map(|(e, _)| *e)
+            .unwrap_or(Self::Overloaded)
+    }
+
+    /**
+     Get the level as a number &ndash; 0 for none, 4 for extra-heavy.
+     */
+    pub fn level(&self) -> i32 {
+        *self as i32
+    }
+
+    /**
+     Get Move multiplier (4e).
+     */
+    pub fn move_multiplier(&self) -> f64 {
+        match self {
+            Self::Overloaded => 0.0,
+            _ => 1.0 - 0.2 * self.level() as f64
+        }
+    }
+
+    /**
+     Get Dodge penalty (a negative number, or zero). 3e Dodge follows (encumbered) Move instead.
+     */
+    pub fn dodge_penalty(&self) -> i32 {
+        -self.level()
+    }
+
+    /**
+     Apply encumbrance to (basic) `mov`e &ndash; a multiplier in 4e, -1 per level in 3e.
+     */
+    pub fn apply_to_move(&self, mov: i32, edition: &GurpsEd) -> i32 {
+        match self {
+            Self::None => mov,
+            Self::Overloaded => 0,
+            _ => match edition {
+                GurpsEd::Ed3 => mov - self.level(),
+                GurpsEd::Ed4 => (mov as f64 * self.move_multiplier()).floor() as i32,
+            }.max(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod encumbrance_tests {
+    use crate::edition::GurpsEd;
+
+    use super::{basic_lift, Encumbrance};
+
+    #[test]
+    fn levels_work() {
+        assert_eq!(20.0, basic_lift(10, &GurpsEd::Ed4));
+        assert_eq!(24.0, basic_lift(12, &GurpsEd::Ed3));
+        assert_eq!(Encumbrance::None, Encumbrance::from_weight(20.0, 20.0));
+        assert_eq!(Encumbrance::Light, Encumbrance::from_weight(20.5, 20.0));
+        assert_eq!(Encumbrance::ExtraHeavy, Encumbrance::from_weight(200.0, 20.0));
+        assert_eq!(Encumbrance::Overloaded, Encumbrance::from_weight(201.0, 20.0));
+        assert_eq!(3, Encumbrance::Medium.apply_to_move(5, &GurpsEd::Ed4));
+        assert_eq!(1, Encumbrance::ExtraHeavy.apply_to_move(4, &GurpsEd::Ed4));
+        assert_eq!(3, Encumbrance::Medium.apply_to_move(5, &GurpsEd::Ed3));
+        assert_eq!(2, Encumbrance::ExtraHeavy.apply_to_move(6, &GurpsEd::Ed3));
+        assert_eq!(-2, Encumbrance::Medium.dodge_penalty());
+    }
+}
@@ -3,11 +3,14 @@ use item::Item;
 use serde::{Deserialize, Serialize};
 use weapon::Weapon;
 
-use crate::misc::costly::Costly;
+use crate::misc::{costly::Costly, mod_grouped::ModGrouped, named::Named, specced::Specced, weighed::Weighed};
+use item::container::Container;
+use spec::Spec;
 
 pub mod weapon;
 pub mod armor;
 pub mod item;
+pub mod spec;
 
 /**
  Various equipment types.
@@ -17,6 +20,7 @@ pub enum Equipment {
     /**
      Armor goes here, with embedded data.
      */
+    #[serde(deserialize_with = "spec::with_legacy_spec")]
     Armor(Armor),
     /**
      Generic items go here, with embedded data.
@@ -37,3 +41,56 @@ impl Costly for Equipment {
         }
     }
 }
+
+impl Named for Equipment {
+    fn name(&self) -> &str {
+        match self {
+            Self::Armor(a) => a.name(),
+            Self::Item(a) => a.name(),
+            Self::Weapon(a) => a.name(),
+        }
+    }
+}
+
+impl ModGrouped for Equipment {
+    fn mod_groups(&self) -> &Vec<String> {
+        match self {
+            Self::Armor(a) => a.mod_groups(),
+            Self::Item(a) => a.mod_groups(),
+            Self::Weapon(a) => a.mod_groups(),
+        }
+    }
+}
+
+impl Specced for Equipment {
+    fn spec(&self) -> &Spec {
+        match self {
+            Self::Armor(a) => a.spec(),
+            Self::Item(a) => a.spec(),
+            Self::Weapon(a) => a.spec(),
+        }
+    }
+}
+
+impl Weighed for Equipment {
+    fn weight(&self) -> Option<f64> {
+        match self {
+            Self::Armor(a) => a.weight(),
+            Self::Item(a) => a.weight(),
+            Self::Weapon(a) => a.weight(),
+        }
+    }
+}
+
+impl Equipment {
+    /**
+     Get container capacity, if it's a container at all.
+     */
+    pub fn container(&self) -> Option<&Container> {
+        match self {
+            Self::Armor(a) => a.container.as_ref(),
+            Self::Item(a) => a.container.as_ref(),
+            Self::Weapon(_) => None,
+        }
+    }
+}
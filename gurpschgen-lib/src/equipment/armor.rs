@@ -2,9 +2,11 @@ use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{damage::{DamageResistance, PassiveDefense}, misc::{costly::Costly, mod_grouped::ModGrouped, named::Named, skilled::Skilled, weighed::Weighed}, skill::Stat};
+use crate::{damage::{DamageResistance, PassiveDefense}, misc::{costly::Costly, mod_grouped::ModGrouped, named::Named, skilled::Skilled, specced::Specced, weighed::Weighed}, skill::Stat};
 
-use super::item::container::Container;
+use crate::hit_location::FACE;
+
+use super::{item::container::Container, spec::{LegacySpecced, Spec, SpecFlag}};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Armor {
@@ -19,7 +21,8 @@ pub struct Armor {
     pub stats_affected: Vec<(Stat, i32)>,
     pub skills_affected: Vec<(String, i32)>,
     pub container: Option<Container>,
-    pub _extra: Vec<String>,
+    #[serde(default)]
+    pub spec: Box<Spec>,
 }
 
 impl Costly for Armor {
@@ -73,6 +76,14 @@ impl Armor {
     pub fn cover(&self) -> &HashSet<i32> {
         &self.cover
     }
+
+    /**
+     See if the armor covers hit location `roll`. Head armor with face protection covers the face too.
+     */
+    pub fn covers(&self, roll: i32) -> bool {
+        self.cover.contains(&roll)
+        || (roll == FACE && self.spec.has(SpecFlag::FaceProtection) && self.cover.iter().any(|c| (3..=4).contains(c)))
+    }
 }
 
 impl ModGrouped for Armor {
@@ -80,3 +91,15 @@ impl ModGrouped for Armor {
         &self.mod_groups
     }
 }
+
+impl Specced for Armor {
+    fn spec(&self) -> &Spec {
+        &self.spec
+    }
+}
+
+impl LegacySpecced for Armor {
+    fn spec_mut(&mut self) -> &mut Spec {
+        &mut self.spec
+    }
+}
@@ -2,7 +2,10 @@ pub mod container;
 
 use serde::{Deserialize, Serialize};
 
-use crate::misc::{costly::Costly, mod_grouped::ModGrouped, named::Named, noted::Noted, skilled::Skilled, weighed::Weighed};
+use crate::misc::{costly::Costly, mod_grouped::ModGrouped, named::Named, noted::Noted, skilled::Skilled, specced::Specced, weighed::Weighed};
+
+use super::spec::Spec;
+use container::Container;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Item {
@@ -12,6 +15,10 @@ pub struct Item {
     pub weight: Option<f64>,// most things have weight, but some have it so neglible that it's irrelevant.
     pub skill: Option<String>,// skill required/skill used with
     pub mod_groups: Vec<String>,
+    #[serde(default)]
+    pub container: Option<Container>,
+    #[serde(default)]
+    pub spec: Box<Spec>,
 }
 
 impl Costly for Item {
@@ -52,3 +59,9 @@ impl ModGrouped for Item {
         &self.mod_groups
     }
 }
+
+impl Specced for Item {
+    fn spec(&self) -> &Spec {
+        &self.spec
+    }
+}
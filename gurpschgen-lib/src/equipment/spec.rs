@@ -0,0 +1,140 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/**
+ Assorted yes/no properties an equipment's specs might list.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum SpecFlag {
+    /// Protects the face (location 5) too.
+    FaceProtection,
+    /// Environmentally sealed.
+    Sealed,
+    /// Easily hidden on one's person.
+    Concealable,
+    /// Flexible (and thus vulnerable to blunt trauma).
+    Flexible,
+    /// Single use only.
+    Disposable,
+    /// Fine to use underwater.
+    Waterproof,
+}
+
+/**
+ Structured "fine print" of equipment specs &ndash; legality, origin and the like.
+
+ Equipment keeps these boxed, as most items have little if any of it.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Spec {
+    /// Legality class.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lc: Option<i32>,
+    /// Tech level.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tl: Option<i32>,
+    /// Country of origin (of a real-life counterpart).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// Year of origin (of a real-life counterpart).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<SpecFlag>,
+    /// Notes about ammunition, as-is.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ammo: Vec<String>,
+    /// Whatever the DTA converter couldn't make sense of.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unparsed: Vec<String>,
+}
+
+impl Spec {
+    /**
+     See if `flag` is set.
+     */
+    pub fn has(&self, flag: SpecFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    /**
+     Set `flag`, unless already set.
+     */
+    pub fn set(&mut self, flag: SpecFlag) {
+        if !self.has(flag) {
+            self.flags.push(flag)
+        }
+    }
+}
+
+/**
+ TL, legality and origin the way older conversions have them &ndash; as loose fields rather than [Spec].
+ */
+#[derive(Default, Deserialize)]
+struct LegacySpec {
+    #[serde(default)]
+    tl: Option<i32>,
+    #[serde(default)]
+    lc: Option<i32>,
+    #[serde(default)]
+    rl_year: Option<i32>,
+    #[serde(default)]
+    rl_country: Option<String>,
+    #[serde(default, rename = "_extra")]
+    extra: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct WithLegacySpec<T> {
+    #[serde(flatten)]
+    item: T,
+    #[serde(flatten)]
+    legacy: LegacySpec,
+}
+
+/**
+ Equipment which older conversions gave loose TL/LC/etc. fields instead of a [Spec].
+ */
+pub(crate) trait LegacySpecced {
+    fn spec_mut(&mut self) -> &mut Spec;
+}
+
+/**
+ Deserialize equipment, folding whatever legacy TL/LC/etc. fields it has into its [Spec].
+ */
+pub(crate) fn with_legacy_spec<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + LegacySpecced,
+{
+    let WithLegacySpec { mut item, legacy } = WithLegacySpec::<T>::deserialize(deserializer)?;
+    let spec = item.spec_mut();
+    spec.tl = spec.tl.or(legacy.tl);
+    spec.lc = spec.lc.or(legacy.lc);
+    spec.year = spec.year.or(legacy.rl_year);
+    spec.country = spec.country.take().or(legacy.rl_country);
+    spec.unparsed.extend(legacy.extra);
+    Ok(item)
+}
+
+#[cfg(test)]
+mod spec_tests {
+    use crate::{context::Context, dta::meta::DtaFile, equipment::{weapon::Weapon, Equipment}, misc::{category::CategoryPayload, specced::Specced}};
+
+    #[test]
+    fn legacy_fields_are_loaded_into_spec() {
+        let json = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../dta2json/datafiles/tl07-equipment.json")).unwrap();
+        let dta: DtaFile = serde_json::from_str(&json).unwrap();
+        let weapon = dta.items[&Context::Equipment].items.values()
+            .find_map(|c| c.items.get("Jati Matic 9x19mm"))
+            .unwrap();
+        let CategoryPayload::Equipment(e @ Equipment::Weapon(Weapon::Ranged(_))) = weapon else { panic!("Not a ranged weapon: {weapon:?}") };
+        assert_eq!(Some(7), e.spec().tl);
+        assert_eq!(Some("FI"), e.spec().country.as_deref());
+
+        let json = r#"{"Ranged":{"name":"Gun","damage":[],"max_damage":null,"acc":1,"ss":null,"rof":null,"rcl":null,
+            "min_range":null,"half_dmg_range":null,"max_range":null,"st_req":null,"tripod":false,"cost":null,"weight":null,
+            "skill":null,"notes":null,"shots":null,"mod_groups":[],"spec":{"tl":9,"lc":2}}}"#;
+        let weapon: Weapon = serde_json::from_str(json).unwrap();
+        assert_eq!((Some(9), Some(2)), (weapon.spec().tl, weapon.spec().lc));
+    }
+}
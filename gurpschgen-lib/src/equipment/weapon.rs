@@ -2,7 +2,9 @@ use melee::Melee;
 use ranged::Ranged;
 use serde::{Deserialize, Serialize};
 
-use crate::{damage::{Damage, DamageDelivery}, misc::{costly::Costly, damaged::Damaged, st_req::STRequired}};
+use crate::{damage::{Damage, DamageDelivery}, misc::{costly::Costly, damaged::Damaged, mod_grouped::ModGrouped, named::Named, specced::Specced, st_req::STRequired, weighed::Weighed}};
+
+use super::spec::Spec;
 
 pub mod melee;
 pub mod ranged;
@@ -10,6 +12,7 @@ pub mod ranged;
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum Weapon {
     Melee(Melee),
+    #[serde(deserialize_with = "super::spec::with_legacy_spec")]
     Ranged(Ranged),
 }
 
@@ -22,6 +25,15 @@ impl STRequired for Weapon {
     }
 }
 
+impl Named for Weapon {
+    fn name(&self) -> &str {
+        match self {
+            Self::Melee(x) => x.name(),
+            Self::Ranged(x) => x.name(),
+        }
+    }
+}
+
 impl Costly for Weapon {
     fn cost(&self) -> f64 {
         match self {
@@ -46,3 +58,30 @@ impl Damaged for Weapon {
         }
     }
 }
+
+impl ModGrouped for Weapon {
+    fn mod_groups(&self) -> &Vec<String> {
+        match self {
+            Self::Melee(x) => x.mod_groups(),
+            Self::Ranged(x) => x.mod_groups(),
+        }
+    }
+}
+
+impl Specced for Weapon {
+    fn spec(&self) -> &Spec {
+        match self {
+            Self::Melee(x) => x.spec(),
+            Self::Ranged(x) => x.spec(),
+        }
+    }
+}
+
+impl Weighed for Weapon {
+    fn weight(&self) -> Option<f64> {
+        match self {
+            Self::Melee(x) => x.weight(),
+            Self::Ranged(x) => x.weight(),
+        }
+    }
+}
@@ -1,6 +1,13 @@
+pub mod parry;
+pub mod reach;
+
+use parry::Parry;
+use reach::Reach;
 use serde::{Deserialize, Serialize};
 
-use crate::{damage::{Damage, DamageDelivery}, misc::{costly::Costly, damaged::Damaged, noted::Noted, skilled::Skilled, st_req::STRequired, weighed::Weighed}};
+use crate::{damage::{Damage, DamageDelivery}, misc::{costly::Costly, damaged::Damaged, mod_grouped::ModGrouped, named::Named, noted::Noted, skilled::Skilled, specced::Specced, st_req::STRequired, weighed::Weighed}};
+
+use crate::equipment::spec::Spec;
 
 /**
  Melee weapon data.
@@ -17,6 +24,15 @@ pub struct Melee {
     pub mod_groups: Vec<String>,
     pub acc: Option<i32>,
     pub st_req: Option<i32>,
+    #[serde(default)]
+    pub spec: Box<Spec>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reach: Vec<Reach>,
+    #[serde(default)]
+    pub parry: Parry,
+    /// Needs both hands to use?
+    #[serde(default)]
+    pub two_handed: bool,
 }
 
 impl Costly for Melee {
@@ -28,6 +44,12 @@ impl Costly for Melee {
     }
 }
 
+impl Named for Melee {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 impl Noted for Melee {
     fn notes(&self) -> Option<&str> {
         if let Some(x) = &self.notes {
@@ -69,3 +91,15 @@ impl Damaged for Melee {
         &self.max_damage
     }
 }
+
+impl ModGrouped for Melee {
+    fn mod_groups(&self) -> &Vec<String> {
+        &self.mod_groups
+    }
+}
+
+impl Specced for Melee {
+    fn spec(&self) -> &Spec {
+        &self.spec
+    }
+}
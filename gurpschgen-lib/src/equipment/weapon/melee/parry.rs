@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::edition::GurpsEd;
+
+/**
+ How (well) a melee weapon parries; embedded modifier.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Parry {
+    /// Ordinary parry, e.g. `0` or `-1`.
+    Yes(i32),
+    /// Fencing weapon, `F`.
+    Fencing(i32),
+    /// Unbalanced, `U` &ndash; can't parry if used to attack that turn.
+    Unbalanced(i32),
+    /// Can't parry at all.
+    No,
+}
+
+impl Default for Parry {
+    fn default() -> Self {
+        Self::Yes(0)
+    }
+}
+
+impl Parry {
+    /**
+     Get Parry at weapon `skill`, if it can parry at all.
+
+     3e: half skill, two thirds for fencing weapons. 4e: 3 + half skill.
+     */
+    pub fn value(&self, skill: i32, edition: &GurpsEd) -> Option<i32> {
+        let (modifier, fencing) = match self {
+            Self::Yes(x) |
+            Self::Unbalanced(x) => (*x, false),
+            Self::Fencing(x) => (*x, true),
+            Self::No => return None
+        };
+        Some(modifier + match edition {
+            GurpsEd::Ed3 if fencing => skill * 2 / 3,
+            GurpsEd::Ed3 => skill / 2,
+            GurpsEd::Ed4 => 3 + skill / 2,
+        })
+    }
+}
+
+#[cfg(test)]
+mod parry_tests {
+    use crate::edition::GurpsEd;
+
+    use super::Parry;
+
+    #[test]
+    fn parry_works() {
+        assert_eq!(Some(6), Parry::Yes(0).value(13, &GurpsEd::Ed3));
+        assert_eq!(Some(8), Parry::Fencing(0).value(13, &GurpsEd::Ed3));
+        assert_eq!(Some(9), Parry::Yes(0).value(13, &GurpsEd::Ed4));
+        assert_eq!(Some(8), Parry::Unbalanced(-1).value(13, &GurpsEd::Ed4));
+        assert_eq!(None, Parry::No.value(13, &GurpsEd::Ed4));
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/**
+ Melee weapon reach.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum Reach {
+    /// **C**lose combat.
+    C,
+    /// Yards.
+    Yards(i32),
+}
+
+impl Display for Reach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::C => write!(f, "C"),
+            Self::Yards(x) => write!(f, "{x}"),
+        }
+    }
+}
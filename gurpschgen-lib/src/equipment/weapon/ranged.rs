@@ -1,3 +1,5 @@
+pub mod ammo;
+pub mod range;
 pub mod rof;
 pub mod shots;
 
@@ -6,7 +8,9 @@ use rof::RoF;
 use serde::{Deserialize, Serialize};
 use shots::Shots;
 
-use crate::{damage::{Damage, DamageDelivery}, misc::{costly::Costly, damaged::Damaged, mod_grouped::ModGrouped, noted::Noted, skilled::Skilled, st_req::STRequired, weighed::Weighed}};
+use crate::{damage::{Damage, DamageDelivery}, misc::{costly::Costly, damaged::Damaged, mod_grouped::ModGrouped, named::Named, noted::Noted, skilled::Skilled, specced::Specced, st_req::STRequired, weighed::Weighed}};
+
+use crate::equipment::spec::{LegacySpecced, Spec};
 
 /**
  Ranged weapon data.
@@ -31,10 +35,8 @@ pub struct Ranged {
     pub notes: Option<String>,
     pub shots: Option<Shots>,
     pub mod_groups: Vec<String>,
-    pub rl_year: Option<i32>,
-    pub rl_country: Option<String>,
-    pub tl: Option<i32>,
-    pub lc: Option<i32>,
+    #[serde(default)]
+    pub spec: Box<Spec>,
 }
 
 impl Costly for Ranged {
@@ -46,6 +48,12 @@ impl Costly for Ranged {
     }
 }
 
+impl Named for Ranged {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 impl Noted for Ranged {
     fn notes(&self) -> Option<&str> {
         if let Some(x) = &self.notes {
@@ -156,3 +164,15 @@ impl ModGrouped for Ranged {
         &self.mod_groups
     }
 }
+
+impl Specced for Ranged {
+    fn spec(&self) -> &Spec {
+        &self.spec
+    }
+}
+
+impl LegacySpecced for Ranged {
+    fn spec_mut(&mut self) -> &mut Spec {
+        &mut self.spec
+    }
+}
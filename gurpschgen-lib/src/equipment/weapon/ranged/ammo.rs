@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use super::shots::Shots;
+
+/**
+ Ammunition state of an owned ranged weapon.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Ammo {
+    /// Rounds (or charge) left in the magazine, cell, belt, tank, ….
+    pub loaded: i32,
+    /// Rounds in the chamber on top of the magazine; see [Shots::MagazinePlus].
+    #[serde(default)]
+    pub chambered: i32,
+}
+
+/**
+ Reasons why a weapon can't be fired or reloaded.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum AmmoError {
+    /// No such weapon.
+    NotFound,
+    /// It doesn't take ammunition at all.
+    NoAmmo,
+    /// Nothing left to fire.
+    Empty,
+    /// No spare loads (or rounds) around.
+    NoSpares,
+    /// Already fully loaded.
+    Full,
+}
+
+impl Ammo {
+    /**
+     Get total rounds (or charge) left.
+     */
+    pub fn rounds(&self) -> i32 {
+        self.loaded + self.chambered
+    }
+
+    /**
+     Keep the chamber topped up from the magazine.
+     */
+    fn rechamber(&mut self, shots: &Shots, total: i32) {
+        self.chambered = total.min(shots.chamber());
+        self.loaded = total - self.chambered;
+    }
+
+    /**
+     Fire (up to) `rounds`.
+
+     **Returns** how many rounds were actually fired &ndash; or [AmmoError::Empty].
+     */
+    pub fn fire(&mut self, shots: &Shots, rounds: i32) -> Result<i32, AmmoError> {
+        let rounds = rounds.max(1);
+        if shots.is_unlimited() {
+            return Ok(rounds);
+        }
+        let fired = rounds.min(self.rounds() / shots.per_shot());
+        if fired == 0 {
+            return Err(AmmoError::Empty);
+        }
+        self.rechamber(shots, self.rounds() - fired * shots.per_shot());
+        Ok(fired)
+    }
+
+    /**
+     Swap in a full load; whatever is chambered stays there.
+
+     **Returns** what was left in the ejected load.
+     */
+    pub fn swap(&mut self, shots: &Shots) -> i32 {
+        self.swap_with(shots, shots.capacity())
+    }
+
+    /**
+     Swap in a (possibly partly used) load of `rounds`; whatever is chambered stays there.
+
+     **Returns** what was left in the ejected load.
+     */
+    pub fn swap_with(&mut self, shots: &Shots, rounds: i32) -> i32 {
+        let ejected = self.loaded;
+        self.rechamber(shots, self.chambered + rounds.clamp(0, shots.capacity()));
+        ejected
+    }
+
+    /**
+     Load up to `rounds` loose rounds.
+
+     **Returns** how many were loaded.
+     */
+    pub fn load(&mut self, shots: &Shots, rounds: i32) -> i32 {
+        let loaded = rounds.clamp(0, shots.capacity() + shots.chamber() - self.rounds());
+        self.rechamber(shots, self.rounds() + loaded);
+        loaded
+    }
+}
+
+#[cfg(test)]
+mod ammo_tests {
+    use crate::equipment::weapon::ranged::shots::{Battery, Shots};
+
+    use super::{Ammo, AmmoError};
+
+    #[test]
+    fn magazine_plus_works() {
+        let shots = Shots::MagazinePlus(15, 1);
+        let mut ammo = shots.full();
+        assert_eq!(16, ammo.rounds());
+        assert_eq!(Ok(3), ammo.fire(&shots, 3));
+        assert_eq!(Ammo { loaded: 12, chambered: 1 }, ammo);
+        assert_eq!(12, ammo.swap(&shots));
+        assert_eq!(16, ammo.rounds());
+        assert_eq!(Ok(16), ammo.fire(&shots, 20));
+        assert_eq!(Err(AmmoError::Empty), ammo.fire(&shots, 1));
+        // Empty chamber gets fed from the fresh magazine.
+        ammo.swap(&shots);
+        assert_eq!(Ammo { loaded: 14, chambered: 1 }, ammo);
+    }
+
+    #[test]
+    fn other_loads_work() {
+        let shots = Shots::Flamethrowerlike(3, 9);
+        let mut ammo = shots.full();
+        assert_eq!(Ok(2), ammo.fire(&shots, 2));
+        assert_eq!(3, ammo.rounds());
+
+        let shots = Shots::DualBarrel;
+        let mut ammo = Ammo { loaded: 0, chambered: 0 };
+        assert_eq!(2, ammo.load(&shots, 5));
+        assert_eq!(Ok(2), ammo.fire(&shots, 3));
+
+        let shots = Shots::Belt(i32::MAX);
+        let mut ammo = Ammo { loaded: 0, chambered: 0 };
+        assert_eq!(Ok(10), ammo.fire(&shots, 10));
+
+        let shots = Shots::Battery(12, Battery::C);
+        let mut ammo = shots.full();
+        assert_eq!(Ok(12), ammo.fire(&shots, 100));
+        assert_eq!(0, ammo.swap(&shots));
+        assert_eq!(12, ammo.rounds());
+    }
+}
@@ -0,0 +1,227 @@
+use crate::damage::Damage;
+
+use super::{rof::RoF, Ranged};
+
+/// Snap shot penalty, when unaimed skill falls short of SS.
+const SNAP_SHOT_PENALTY: i32 = -4;
+/// Max bonus from aiming longer than one turn.
+const MAX_EXTRA_AIM: i32 = 2;
+
+/**
+ Get the speed/range penalty for `yards` &ndash; target's speed and range (in yards) summed.
+
+ Goes -1 per step along 2, 3, 5, 7, 10, 15, 20, 30, 50, 70, 100, 150, … yards.
+ Non-finite `yards` are clamped: NaN counts as no distance at all, infinity as the farthest finite one.
+ */
+pub fn speed_range_penalty(yards: f64) -> i32 {
+    const STEPS: [f64; 6] = [1.0, 1.5, 2.0, 3.0, 5.0, 7.0];
+    let yards = if yards.is_nan() { 0.0 } else { yards.min(f64::MAX) };
+    if yards <= 2.0 {
+        return 0;
+    }
+    let mut penalty = 0;
+    for limit in [3.0, 5.0, 7.0] {
+        penalty -= 1;
+        if yards <= limit {
+            return penalty;
+        }
+    }
+    let mut scale = 10.0;
+    loop {
+        for step in STEPS {
+            penalty -= 1;
+            if yards <= step * scale {
+                return penalty;
+            }
+        }
+        scale *= 10.0;
+    }
+}
+
+/**
+ How a shot is taken.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aim {
+    /// Turns spent aiming; `0` is a snap shot.
+    pub turns: i32,
+    /// Braced (or using a tripod/bipod)?
+    pub braced: bool,
+}
+
+/**
+ Reasons why a shot can't be taken.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShotError {
+    /// Target is within the weapon's minimum range.
+    TooClose,
+    /// Target is past the weapon's max range &ndash; or at no finite distance/speed at all.
+    OutOfRange,
+    /// More rounds than the weapon's RoF allows; the allowed max.
+    TooManyRounds(i32),
+}
+
+/**
+ Outcome of lining up a shot.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shot {
+    /// Effective skill for the (first) shot.
+    pub skill: i32,
+    /// Rounds fired.
+    pub rounds: i32,
+    /// Is the target past half damage range?
+    pub half_damage: bool,
+}
+
+impl Shot {
+    /**
+     Get how many rounds hit when the attack roll was made by `margin`.
+
+     Full auto fire hits with one round plus one per full |Rcl| the roll was made by,
+     up to rounds fired. Other fire modes roll per shot (see [Ranged::follow_up_skill]).
+     */
+    pub fn hits(&self, weapon: &Ranged, margin: i32) -> i32 {
+        if margin < 0 {
+            0
+        } else if let Some(RoF::FullAuto(_)) = weapon.rof() {
+            let rcl = weapon.rcl().unwrap_or(-1).abs().max(1);
+            (1 + margin / rcl).min(self.rounds)
+        } else {
+            1
+        }
+    }
+}
+
+impl Ranged {
+    /**
+     Get the most rounds the weapon can fire in a turn at `skill`.
+     */
+    pub fn max_rounds(&self, skill: i32) -> i32 {
+        match self.rof() {
+            Some(RoF::FullAuto(x)) |
+            Some(RoF::SemiAuto(x)) |
+            Some(RoF::Trigger(x)) => *x,
+            Some(RoF::Skill(x)) => (skill / (*x).max(1)).max(1),
+            Some(RoF::Slow(..)) |
+            None => 1
+        }
+    }
+
+    /**
+     Line up a shot of `rounds` at a target `distance` yards away moving at `speed` yards/sec.
+
+     Aiming adds Acc (+1 if braced, +1 per extra turn up to +2). An unaimed shot
+     whose skill falls short of SS gets a -4 snap shot penalty. Full auto fire gets
+     +1 per 4 rounds past the first.
+     */
+    pub fn shot(&self, skill: i32, distance: f64, speed: f64, aim: Aim, rounds: i32) -> Result<Shot, ShotError> {
+        if !(distance + speed).is_finite() {
+            return Err(ShotError::OutOfRange);
+        }
+        if self.min_range().is_some_and(|r| distance < r as f64) {
+            return Err(ShotError::TooClose);
+        }
+        if self.max_range().is_some_and(|r| distance > r as f64) {
+            return Err(ShotError::OutOfRange);
+        }
+        let rounds = rounds.max(1);
+        let max_rounds = self.max_rounds(skill);
+        if rounds > max_rounds {
+            return Err(ShotError::TooManyRounds(max_rounds));
+        }
+
+        let mut skill = skill + speed_range_penalty(distance + speed);
+        if aim.turns > 0 {
+            skill += self.acc() + if aim.braced {1} else {0} + (aim.turns - 1).min(MAX_EXTRA_AIM);
+        } else if self.ss().is_some_and(|ss| skill < ss) {
+            skill += SNAP_SHOT_PENALTY;
+        }
+        if let Some(RoF::FullAuto(_)) = self.rof() {
+            skill += (rounds - 1) / 4;
+        }
+
+        Ok(Shot {
+            skill,
+            rounds,
+            half_damage: self.half_dmg_range().is_some_and(|r| distance > r as f64),
+        })
+    }
+
+    /**
+     Get skill for the `nth` (1-based) of several non-automatic shots in a turn; Rcl accumulates.
+     */
+    pub fn follow_up_skill(&self, shot: &Shot, nth: i32) -> i32 {
+        shot.skill + self.rcl().unwrap_or(0) * (nth - 1).max(0)
+    }
+
+    /**
+     Get damage dealt at `distance` yards, halved past half damage range.
+     */
+    pub fn damage_at(&self, distance: f64) -> Vec<Damage> {
+        let half = self.half_dmg_range().is_some_and(|r| distance > r as f64);
+        self.damage().iter()
+            .map(|d| if half { Damage::new(d.damage_type(), d.delivery().halved()) } else { d.clone() })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod range_tests {
+    use crate::{damage::{Damage, DamageDelivery}, equipment::weapon::ranged::{rof::RoF, Ranged}};
+
+    use super::{speed_range_penalty, Aim, ShotError};
+
+    fn smg() -> Ranged {
+        Ranged {
+            name: "SMG".to_string(), damage: vec![Damage::Cr(DamageDelivery::Dice(2, 1))], max_damage: None,
+            acc: 6, ss: Some(10), rof: Some(RoF::FullAuto(12)), rcl: Some(-2),
+            min_range: None, half_dmg_range: Some(150), max_range: Some(1900), st_req: None, tripod: false,
+            cost: None, weight: None, skill: None, notes: None, shots: None, mod_groups: vec![], spec: Default::default(),
+        }
+    }
+
+    #[test]
+    fn speed_range_table_works() {
+        for (yards, penalty) in [(1.0, 0), (2.0, 0), (3.0, -1), (5.0, -2), (7.0, -3), (10.0, -4),
+                                 (11.0, -5), (15.0, -5), (20.0, -6), (100.0, -10), (101.0, -11), (1000.0, -16)] {
+            assert_eq!(penalty, speed_range_penalty(yards), "{yards} yards");
+        }
+    }
+
+    #[test]
+    fn non_finite_speed_range_works() {
+        assert_eq!(0, speed_range_penalty(f64::NAN));
+        assert_eq!(speed_range_penalty(f64::MAX), speed_range_penalty(f64::INFINITY));
+        assert!(speed_range_penalty(f64::INFINITY) < speed_range_penalty(1e300));
+        let aimed = Aim { turns: 1, braced: false };
+        assert_eq!(Err(ShotError::OutOfRange), smg().shot(12, f64::NAN, 0.0, aimed, 1));
+        assert_eq!(Err(ShotError::OutOfRange), smg().shot(12, 20.0, f64::INFINITY, aimed, 1));
+    }
+
+    #[test]
+    fn shot_works() {
+        let smg = smg();
+        let aimed = Aim { turns: 1, braced: true };
+        let shot = smg.shot(12, 20.0, 0.0, aimed, 1).unwrap();
+        assert_eq!(12 - 6 + 6 + 1, shot.skill);
+        // Snap shot: 12-6 < SS 10.
+        assert_eq!(2, smg.shot(12, 20.0, 0.0, Aim { turns: 0, braced: false }, 1).unwrap().skill);
+        // Full auto: +2 for 9-12 rounds; margin 5 at Rcl -2 → 3 hits.
+        let burst = smg.shot(12, 20.0, 0.0, aimed, 12).unwrap();
+        assert_eq!(15, burst.skill);
+        assert_eq!(3, burst.hits(&smg, 5));
+        assert_eq!(0, burst.hits(&smg, -1));
+        assert_eq!(Err(ShotError::TooManyRounds(12)), smg.shot(12, 20.0, 0.0, aimed, 13));
+        assert_eq!(Err(ShotError::OutOfRange), smg.shot(12, 2000.0, 0.0, aimed, 1));
+    }
+
+    #[test]
+    fn half_damage_works() {
+        let smg = smg();
+        assert_eq!(vec![Damage::Cr(DamageDelivery::Dice(2, 1))], smg.damage_at(150.0));
+        assert_eq!(vec![Damage::Cr(DamageDelivery::DiceMul(2, 1, 0.5))], smg.damage_at(151.0));
+        assert!(smg.shot(12, 151.0, 0.0, Aim { turns: 1, braced: false }, 1).unwrap().half_damage);
+    }
+}
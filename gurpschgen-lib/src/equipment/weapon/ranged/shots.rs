@@ -1,29 +1,79 @@
 use serde::{Deserialize, Serialize};
 
+use crate::misc::{costly::Costly, weighed::Weighed};
+
+use super::ammo::Ammo;
+
 /**
  Various high-tech energy battery types.
  */
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum Battery {
     AAA, AA, A, B, C, D, E, F,
 }
 
-impl From<&str> for Battery {
-    fn from(value: &str) -> Self {
-        match value {
-            "AAA" => Self::AAA,
-            "AA" => Self::AA,
-            "A" => Self::A,
-            "B" => Self::B,
-            "C" => Self::C,
-            "D" => Self::D,
-            "E" => Self::E,
-            "F" => Self::F,
-            n => todo!("FATAL: battery type \"{n}\" not implemented?!")
+impl TryFrom<&str> for Battery {
+    type Error = String;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.trim() {
+            "AAA" => Ok(Self::AAA),
+            "AA" => Ok(Self::AA),
+            "A" => Ok(Self::A),
+            "B" => Ok(Self::B),
+            "C" => Ok(Self::C),
+            "D" => Ok(Self::D),
+            "E" => Ok(Self::E),
+            "F" => Ok(Self::F),
+            n => Err(format!("battery type \"{n}\" not known"))
         }
     }
 }
 
+impl Battery {
+    /**
+     Get the cell's name, e.g. "C cell". Spare cells are recognized by it.
+     */
+    pub fn cell_name(&self) -> String {
+        format!("{self:?} cell")
+    }
+}
+
+impl Costly for Battery {
+    /**
+     Get $cost of a cell. AA through E follow the TL8-10 equipment data; AAA and F are extrapolated.
+     */
+    fn cost(&self) -> f64 {
+        match self {
+            Self::AAA => 1.0,
+            Self::AA => 2.0,
+            Self::A => 10.0,
+            Self::B => 30.0,
+            Self::C => 100.0,
+            Self::D => 500.0,
+            Self::E => 2000.0,
+            Self::F => 8000.0,
+        }
+    }
+}
+
+impl Weighed for Battery {
+    /**
+     Get weight (lbs) of a cell. AA through E follow the TL8-10 equipment data; AAA and F are extrapolated.
+     */
+    fn weight(&self) -> Option<f64> {
+        Some(match self {
+            Self::AAA => 0.00001,
+            Self::AA => 0.000125,
+            Self::A => 0.0025,
+            Self::B => 0.05,
+            Self::C => 0.5,
+            Self::D => 5.0,
+            Self::E => 20.0,
+            Self::F => 200.0,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Shots {
     Battery(i32, Battery),
@@ -37,3 +87,80 @@ pub enum Shots {
     /// 1×barrel shotgun, bolt-action rifle, one-shot Derringer, etc.
     Single,
 }
+
+impl Shots {
+    /**
+     Get how much a full load (magazine, cell, belt, tank, …) holds.
+     */
+    pub fn capacity(&self) -> i32 {
+        match self {
+            Self::Battery(x, _) |
+            Self::Belt(x) |
+            Self::Box(x) |
+            Self::Magazine(x) |
+            Self::MagazinePlus(x, _) => *x,
+            Self::Flamethrowerlike(_, y) => *y,
+            Self::DualBarrel => 2,
+            Self::Single => 1,
+        }
+    }
+
+    /**
+     Get how many rounds fit in the chamber on top of the magazine.
+     */
+    pub fn chamber(&self) -> i32 {
+        match self {
+            Self::MagazinePlus(_, x) => *x,
+            _ => 0
+        }
+    }
+
+    /**
+     Get how much of the load a single shot uses up.
+
+     `(x)y` loads are good for `x` bursts out of `y`.
+     */
+    pub fn per_shot(&self) -> i32 {
+        match self {
+            Self::Flamethrowerlike(x, y) => (y / (*x).max(1)).max(1),
+            _ => 1
+        }
+    }
+
+    /**
+     Is the weapon fed externally, i.e. it never runs dry?
+     */
+    pub fn is_unlimited(&self) -> bool {
+        matches!(self, Self::Belt(i32::MAX))
+    }
+
+    /**
+     Is the weapon reloaded round by round (as opposed to swapping a whole load)?
+     */
+    pub fn is_loose(&self) -> bool {
+        matches!(self, Self::Single | Self::DualBarrel)
+    }
+
+    /**
+     Get the name of a spare load for `weapon`, e.g. "Glock 20 10mm magazine" or "C cell".
+     */
+    pub fn spare_name(&self, weapon: &str) -> String {
+        match self {
+            Self::Battery(_, b) => b.cell_name(),
+            Self::Belt(_) => format!("{weapon} belt"),
+            Self::Box(_) => format!("{weapon} box"),
+            Self::Flamethrowerlike(..) => format!("{weapon} tank"),
+            Self::Magazine(_) |
+            Self::MagazinePlus(..) => format!("{weapon} magazine"),
+            Self::DualBarrel |
+            Self::Single => format!("{weapon} rounds"),
+        }
+    }
+
+    /**
+     Get a fully loaded [Ammo] state.
+     */
+    pub fn full(&self) -> Ammo {
+        Ammo { loaded: self.capacity(), chambered: self.chamber() }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{adq::Adq, context::{Context, ContextPayload}, dta::genre::Genre, equipment::{item::Item, Equipment}, misc::category::{Category, CategoryPayload}, skill::{Skill, SkillRoot}};
+
+/**
+ An (unbought) advantage/disadvantage/package, costing `cost` per level, up to level 3.
+ */
+pub(crate) fn adq(name: &str, cost: i32) -> Adq {
+    Adq {
+        name: name.to_string(), initial_cost: cost, cost_increment: cost, level: 0, max_level: 3,
+        bonus_mods: vec![], given: vec![], mod_groups: vec![], equipment_given: vec![], cash_cost: 0.0,
+    }
+}
+
+/**
+ An (unlearned) skill, based on `base`.
+ */
+pub(crate) fn skill(name: &str, base: SkillRoot) -> Skill {
+    Skill {
+        name: name.to_string(), rank: 0, base,
+        defaults: vec![], affected_by_bonuses: vec![], tl_dependant: false,
+        increases_counters: vec![], gives: vec![], gives_bonuses: vec![],
+    }
+}
+
+/**
+ A plain item.
+ */
+pub(crate) fn item(name: &str, cost: f64, weight: f64) -> Equipment {
+    Equipment::Item(Item {
+        name: name.to_string(), notes: None, cost: Some(cost), weight: Some(weight),
+        skill: None, mod_groups: vec![], container: None, spec: Default::default(),
+    })
+}
+
+/// A category's worth of genre contents: `(context, category, [(name, item)])`.
+pub(crate) type Shelf<'a> = (Context, &'a str, Vec<(&'a str, CategoryPayload)>);
+
+/**
+ A genre with the given contents.
+ */
+pub(crate) fn genre(contents: Vec<Shelf>) -> Genre {
+    let mut genre = Genre::new();
+    for (context, cat, items) in contents {
+        let mut c = Category::new(cat);
+        c.items = items.into_iter().map(|(n, p)| (n.to_string(), p)).collect();
+        genre.items.entry(context.clone()).or_insert_with(|| ContextPayload::new(context)).items.insert(cat.to_string(), c);
+    }
+    genre
+}
@@ -1,4 +1,24 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/**
+ Character's gender. Whatever a campaign makes of it (if anything) is up to its [Config][crate::config::Config].
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum Gender {
     Female,
-    Male
+    Male,
+    /// Anything else a campaign cares to have.
+    Other(String),
+}
+
+impl Display for Gender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Female => write!(f, "Female"),
+            Self::Male => write!(f, "Male"),
+            Self::Other(x) => write!(f, "{x}"),
+        }
+    }
 }
@@ -0,0 +1,301 @@
+use crate::{attrib::AttributeType, ch::Ch, config::{BudgetError, Config}, context::Context, dice::{DieRoller, SeededRoller}, dta::genre::Genre, misc::{category::CategoryPayload, costly::Costly, named::Named}, shop::Shop};
+
+/// Starting cash for equipment, unless told otherwise.
+const DEFAULT_CASH: f64 = 1000.0;
+/// Share of points (left after disadvantages) spent on attributes.
+const ATTRIBUTE_SHARE: f64 = 0.5;
+/// Share of points (left after disadvantages) spent on advantages; skills get the rest.
+const ADVANTAGE_SHARE: f64 = 0.2;
+/// Give up picking things after this many misses in a row.
+const MAX_MISSES: usize = 20;
+
+/**
+ Reasons why a character can't be generated.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeneratorError {
+    /// No such (required) package in the genre.
+    PackageNotFound(String),
+    /// Required packages alone break the campaign's limits.
+    Budget(BudgetError),
+}
+
+/**
+ Random (NPC) character generator, spending points on what a [Genre] has to offer.
+ */
+pub struct Generator<'a> {
+    genre: &'a Genre,
+    config: &'a Config,
+    points: i32,
+    cash: f64,
+    packages: Vec<String>,
+    weights: Vec<(String, u32)>,
+    seed: Option<u64>,
+}
+
+type Candidate<'a> = (u32, &'a str, &'a CategoryPayload);
+
+/**
+ Pick one of `candidates`, by weight.
+ */
+fn pick<'c, T>(roller: &mut SeededRoller, candidates: &'c [(u32, &str, T)]) -> Option<&'c T> {
+    let total: u32 = candidates.iter().map(|c| c.0).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut r = roller.roll_die(total as i32) as u32;
+    candidates.iter()
+        .find(|c| if r <= c.0 { true } else { r -= c.0; false })
+        .map(|c| &c.2)
+}
+
+impl<'a> Generator<'a> {
+    /**
+     Instantiate a generator for `genre`, within `config`'s budget and limits.
+     */
+    pub fn new(genre: &'a Genre, config: &'a Config) -> Self {
+        Self { genre, config, points: config.points, cash: DEFAULT_CASH, packages: vec![], weights: vec![], seed: None }
+    }
+
+    /**
+     Spend `points` instead of the campaign's budget.
+     */
+    pub fn with_points(mut self, points: i32) -> Self {
+        self.points = points;
+        self
+    }
+
+    /**
+     Spend `cash` on equipment.
+     */
+    pub fn with_cash(mut self, cash: f64) -> Self {
+        self.cash = cash;
+        self
+    }
+
+    /**
+     Require package `name`.
+     */
+    pub fn with_package(mut self, name: &str) -> Self {
+        self.packages.push(name.to_string());
+        self
+    }
+
+    /**
+     Weigh picks from `category` (of any context); default weight is `1`, and `0` leaves the category out.
+     */
+    pub fn with_weight(mut self, category: &str, weight: u32) -> Self {
+        self.weights.push((category.to_string(), weight));
+        self
+    }
+
+    /**
+     Use a fixed `seed` &ndash; same seed, same character.
+     */
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    fn weight(&self, category: &str) -> u32 {
+        self.weights.iter()
+            .find(|(c, _)| c.eq_ignore_ascii_case(category))
+            .map_or(1, |(_, w)| *w)
+    }
+
+    /**
+     Get allowed candidates within `context`, sorted by name (and category) to keep picks repeatable.
+     */
+    fn candidates(&self, context: Context) -> Vec<Candidate<'a>> {
+        let Some(ctx) = self.genre.items.get(&context) else { return vec![] };
+        let mut candidates: Vec<_> = ctx.items.iter()
+            .map(|(cat_name, cat)| (self.weight(cat_name), (cat_name, cat)))
+            .filter(|(w, _)| *w > 0)
+            .flat_map(|(w, (cat_name, cat))| cat.items.iter().map(move |(n, p)| (w, n.as_str(), cat_name.as_str(), p)))
+            .filter(|(_, n, _, _)| self.config.is_allowed(n))
+            .collect();
+        candidates.sort_by(|a, b| (a.1, a.2).cmp(&(b.1, b.2)));
+        candidates.into_iter().map(|(w, n, _, p)| (w, n, p)).collect()
+    }
+
+    /**
+     Take (level 1) advantages &ndash; or with `gain`, disadvantages &ndash; worth up to `budget` points.
+     */
+    fn take_adqs(&self, roller: &mut SeededRoller, ch: &mut Ch, candidates: &[Candidate], budget: f64, gain: bool) {
+        let (mut spent, mut misses) = (0.0, 0);
+        while misses < MAX_MISSES {
+            let Some(CategoryPayload::Advantage(adq) | CategoryPayload::Disadvantage(adq)) = pick(roller, candidates) else { break };
+            let mut adq = adq.clone();
+            adq.level = 1;
+            let cost = if gain { -adq.cost() } else { adq.cost() };
+            if cost <= 0.0 || spent + cost > budget || ch.advantage_level(adq.name()) > 0 {
+                misses += 1;
+                continue;
+            }
+            spent += cost;
+            ch.advantages.push(adq);
+            misses = 0;
+        }
+    }
+
+    /**
+     Raise random attributes, one level at a time, worth up to `budget` points.
+     */
+    fn raise_attributes(&self, roller: &mut SeededRoller, ch: &mut Ch, budget: f64) {
+        let (start, genre_max, mut misses) = (ch.cost(), self.genre.max_attr_default(), 0);
+        while misses < MAX_MISSES {
+            let attrib = AttributeType::ALL[roller.roll_die(AttributeType::ALL.len() as i32) as usize - 1];
+            if ch.raise_attribute(attrib, 1, genre_max).is_err() {
+                misses += 1;
+            } else if ch.cost() - start > budget {
+                let _ = ch.raise_attribute(attrib, -1, genre_max);
+                misses += 1;
+            } else {
+                misses = 0;
+            }
+        }
+    }
+
+    /**
+     Learn (or raise) random skills, one rank at a time, until `ch` costs `points`.
+     */
+    fn learn_skills(&self, roller: &mut SeededRoller, ch: &mut Ch, candidates: &[Candidate], points: f64) {
+        let mut misses = 0;
+        while misses < MAX_MISSES {
+            let Some(CategoryPayload::Skill(skill)) = pick(roller, candidates) else { break };
+            let before = ch.clone();
+            match ch.skills.iter_mut().find(|s| s.name == skill.name) {
+                Some(s) => s.rank += 1,
+                None => {
+                    let mut skill = skill.clone();
+                    skill.rank = 1;
+                    ch.skills.push(skill);
+                }
+            }
+            if ch.cost() > points {
+                *ch = before;
+                misses += 1;
+            } else {
+                misses = 0;
+            }
+        }
+    }
+
+    /**
+     Buy random equipment (one of each, at most) with whatever cash `ch` has.
+     */
+    fn buy_equipment(&self, roller: &mut SeededRoller, ch: &mut Ch) {
+        let shop = Shop::new(self.genre).with_campaign(self.config);
+        let wares: Vec<_> = shop.browse(None).into_iter()
+            .map(|(cat, e)| (self.weight(cat), e.name(), e))
+            .filter(|(w, _, _)| *w > 0)
+            .collect();
+        let mut misses = 0;
+        while misses < MAX_MISSES {
+            let Some(e) = pick(roller, &wares) else { break };
+            if ch.inventory.count(e.name()) > 0 || shop.buy(ch, e.name(), 1).is_err() {
+                misses += 1;
+            } else {
+                misses = 0;
+            }
+        }
+    }
+
+    /**
+     Generate a character called `name`.
+     */
+    pub fn generate(&self, name: &str) -> Result<Ch, GeneratorError> {
+        let mut roller = self.seed.map_or_else(SeededRoller::from_clock, SeededRoller::new);
+        let mut ch = Ch::new(name);
+        ch.cash = self.cash;
+        for p in &self.packages {
+            match self.genre.find(&Context::Package, p) {
+                Some(CategoryPayload::Package(package)) => { ch.apply_package(package, self.genre); },
+                _ => return Err(GeneratorError::PackageNotFound(p.clone()))
+            }
+        }
+
+        let points = self.points as f64;
+        let cap = self.config.disadvantage_cap.map_or(points / 2.0, |c| c as f64) + ch.disadvantage_points();
+        let gain = cap * roller.roll_die(100) as f64 / 100.0;
+        self.take_adqs(&mut roller, &mut ch, &self.candidates(Context::Disadvantage), gain, true);
+
+        let available = points - ch.cost();
+        self.raise_attributes(&mut roller, &mut ch, available * ATTRIBUTE_SHARE);
+        self.take_adqs(&mut roller, &mut ch, &self.candidates(Context::Advantage), available * ADVANTAGE_SHARE, false);
+        self.learn_skills(&mut roller, &mut ch, &self.candidates(Context::Skill), points);
+        self.buy_equipment(&mut roller, &mut ch);
+
+        let mut config = self.config.clone();
+        config.points = self.points;
+        config.check_budget(&ch).map_err(GeneratorError::Budget)?;
+        Ok(ch)
+    }
+}
+
+#[cfg(test)]
+mod generator_tests {
+    use crate::{adq::Adq, config::Config, context::Context, dta::genre::Genre, edition::GurpsEd, fixtures::{self, item, skill}, misc::{category::CategoryPayload, costly::Costly}, skill::{DifficultyRating, SkillRoot, Stat}};
+
+    use super::{Generator, GeneratorError};
+
+    fn adq(name: &str, cost: i32) -> Adq {
+        Adq { cost_increment: 0, max_level: 1, equipment_given: vec![("Rope".into(), 1)], ..fixtures::adq(name, cost) }
+    }
+
+    fn genre() -> Genre {
+        let skill = |name| CategoryPayload::Skill(skill(name, SkillRoot::M { stat: Stat::IQ, diff: DifficultyRating::A }));
+        let item = |name, cost| CategoryPayload::Equipment(item(name, cost, 1.0));
+        fixtures::genre(vec![
+            (Context::Advantage, "Mental", vec![("Alertness", CategoryPayload::Advantage(adq("Alertness", 5))), ("Luck", CategoryPayload::Advantage(adq("Luck", 15)))]),
+            (Context::Disadvantage, "Mental", vec![("Greed", CategoryPayload::Disadvantage(adq("Greed", -15))), ("Laziness", CategoryPayload::Disadvantage(adq("Laziness", -10)))]),
+            (Context::Package, "Jobs", vec![("Sailor", CategoryPayload::Package(adq("Sailor", 10)))]),
+            (Context::Skill, "Outdoors", vec![("Survival", skill("Survival")), ("Tracking", skill("Tracking"))]),
+            (Context::Skill, "Social", vec![("Carousing", skill("Carousing"))]),
+            (Context::Equipment, "Gear", vec![("Rope", item("Rope", 10.0)), ("Lantern", item("Lantern", 20.0)), ("Tent", item("Tent", 500.0))]),
+        ])
+    }
+
+    #[test]
+    fn generating_works() {
+        let genre = genre();
+        let mut config = Config::default_tl8(GurpsEd::Ed4);
+        config.forbidden.insert("Luck".into());
+        config.disadvantage_cap = Some(20);
+        let generator = Generator::new(&genre, &config)
+            .with_points(75)
+            .with_cash(100.0)
+            .with_package("Sailor")
+            .with_weight("Social", 0)
+            .with_seed(42);
+        let ch = generator.generate("Bosun").unwrap();
+        assert!((70.0..=75.0).contains(&ch.cost()));
+        assert!(ch.disadvantage_points() >= -20.0);
+        assert_eq!(1, ch.packages.len());
+        assert!(ch.advantage_level("Luck") == 0);
+        assert!(ch.skills.iter().all(|s| s.name != "Carousing"));
+        assert!(ch.inventory.count("Tent") == 0);
+        assert!(ch.cash >= 0.0);
+
+        let again = generator.generate("Bosun").unwrap();
+        assert_eq!(ch.ledger(), again.ledger());
+        assert_eq!(ch.cash, again.cash);
+
+        assert_eq!(Err(GeneratorError::PackageNotFound("Pirate".into())),
+            Generator::new(&genre, &config).with_package("Pirate").generate("X").map(|_| ()));
+    }
+
+    #[test]
+    fn candidates_order_works() {
+        let skill = |name| CategoryPayload::Skill(skill(name, SkillRoot::M { stat: Stat::IQ, diff: DifficultyRating::A }));
+        let genre = fixtures::genre(vec![
+            (Context::Skill, "Outdoors", vec![("Survival", skill("Survival")), ("Tracking", skill("Tracking"))]),
+            (Context::Skill, "Arctic", vec![("Survival", skill("Survival"))]),
+        ]);
+        let config = Config::default_tl8(GurpsEd::Ed4);
+        // Same name, different category - told apart by weight.
+        let generator = Generator::new(&genre, &config).with_weight("Arctic", 3);
+        assert_eq!(vec![(3, "Survival"), (1, "Survival"), (1, "Tracking")],
+            generator.candidates(Context::Skill).iter().map(|(w, n, _)| (*w, *n)).collect::<Vec<_>>());
+    }
+}
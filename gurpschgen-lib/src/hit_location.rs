@@ -0,0 +1,57 @@
+use std::ops::RangeInclusive;
+
+/**
+ A hit location, as per MakeChar's numbering (3d6 roll).
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct HitLocation {
+    pub name: &'static str,
+    /// 3d6 roll(s) which hit the location.
+    pub rolls: RangeInclusive<i32>,
+    /// Penalty to hit the location deliberately.
+    pub penalty: i32,
+}
+
+/// Location the "Face protection" flag extends head armor to.
+pub const FACE: i32 = 5;
+
+/**
+ The hit location table, in roll order.
+ */
+pub const HIT_LOCATIONS: [HitLocation; 11] = [
+    HitLocation { name: "Brain", rolls: 3..=4, penalty: -7 },
+    HitLocation { name: "Face", rolls: 5..=5, penalty: -5 },
+    HitLocation { name: "Right arm", rolls: 6..=6, penalty: -2 },
+    HitLocation { name: "Hands", rolls: 7..=7, penalty: -4 },
+    HitLocation { name: "Left arm", rolls: 8..=8, penalty: -2 },
+    HitLocation { name: "Torso", rolls: 9..=10, penalty: 0 },
+    HitLocation { name: "Groin", rolls: 11..=11, penalty: -3 },
+    HitLocation { name: "Right leg", rolls: 12..=12, penalty: -2 },
+    HitLocation { name: "Left leg", rolls: 13..=14, penalty: -2 },
+    HitLocation { name: "Feet", rolls: 15..=16, penalty: -4 },
+    HitLocation { name: "Vitals", rolls: 17..=18, penalty: -3 },
+];
+
+/**
+ Get the location hit by a 3d6 `roll`.
+
+ **Returns** `None` if `roll` is out of 3..18 range.
+ */
+pub fn location(roll: i32) -> Option<&'static HitLocation> {
+    HIT_LOCATIONS.iter().find(|l| l.rolls.contains(&roll))
+}
+
+#[cfg(test)]
+mod hit_location_tests {
+    use super::{location, HIT_LOCATIONS};
+
+    #[test]
+    fn table_is_complete() {
+        for roll in 3..=18 {
+            assert!(location(roll).is_some(), "{roll} not covered");
+        }
+        assert_eq!(16, HIT_LOCATIONS.iter().map(|l| l.rolls.clone().count()).sum::<usize>());
+        assert_eq!("Torso", location(10).unwrap().name);
+        assert_eq!(None, location(19));
+    }
+}
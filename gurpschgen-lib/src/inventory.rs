@@ -0,0 +1,552 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{damage::{Damage, DamageResistance, PassiveDefense}, dta::genre::Genre, equipment::{item::{container::Container, Item}, weapon::{ranged::{ammo::{Ammo, AmmoError}, shots::Shots}, Weapon}, Equipment}, misc::{costly::Costly, damaged::Damaged, mod_grouped::ModGrouped, named::Named, weighed::Weighed}, modifier::effect::{self, EffectModifier, Target}};
+
+/**
+ An owned piece (or a stack) of [Equipment].
+ */
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Possession {
+    pub equipment: Equipment,
+    pub quantity: usize,
+    /// Is it worn (as opposed to merely carried)?
+    #[serde(default)]
+    pub worn: bool,
+    /// Whatever is stowed inside, if it's a container.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contents: Vec<Possession>,
+    /// Selected modifiers, keyed by their mod group &ndash; at most one per group.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<(String, EffectModifier)>,
+    /// Ammunition state, for ranged weapons &ndash; or what's left in a spare load; unknown is taken as fully loaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ammo: Option<Ammo>,
+}
+
+/**
+ Reasons why something can't be stowed into a [Possession].
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum StowError {
+    /// No such possession to stow things into.
+    NotFound,
+    /// It isn't a container at all.
+    NotAContainer,
+    /// Liquid containers take only liquids.
+    LiquidOnly,
+    /// Not enough room; `capacity` and what'd be the resulting `load` (lbs).
+    Overfull { capacity: f64, load: f64 },
+}
+
+impl Named for Possession {
+    fn name(&self) -> &str {
+        self.equipment.name()
+    }
+}
+
+impl Weighed for Possession {
+    /**
+     Get total weight of the stack, including contents.
+     */
+    fn weight(&self) -> Option<f64> {
+        Some(self.unit_weight() * self.quantity as f64 + self.contents_weight())
+    }
+}
+
+impl Costly for Possession {
+    /**
+     Get total cost of the stack, including contents.
+     */
+    fn cost(&self) -> f64 {
+        self.unit_cost() * self.quantity as f64 + self.contents.iter().map(|p| p.cost()).sum::<f64>()
+    }
+}
+
+impl Possession {
+    /**
+     Instantiate a [Possession] of `quantity` of `equipment`.
+     */
+    pub fn new(equipment: Equipment, quantity: usize) -> Self {
+        let ammo = match &equipment {
+            Equipment::Weapon(Weapon::Ranged(r)) => r.shots().as_ref().map(|s| s.full()),
+            _ => None
+        };
+        Self { equipment, quantity, worn: false, contents: vec![], modifiers: vec![], ammo }
+    }
+
+    /**
+     Get the ammunition model, if it's a ranged weapon that takes any.
+     */
+    pub fn shots(&self) -> Option<&Shots> {
+        match &self.equipment {
+            Equipment::Weapon(Weapon::Ranged(r)) => r.shots().as_ref(),
+            _ => None
+        }
+    }
+
+    /**
+     Fire (up to) `rounds`.
+
+     **Returns** how many rounds were actually fired.
+     */
+    pub fn fire(&mut self, rounds: i32) -> Result<i32, AmmoError> {
+        let shots = self.shots().ok_or(AmmoError::NoAmmo)?.clone();
+        self.ammo.get_or_insert_with(|| shots.full()).fire(&shots, rounds)
+    }
+
+    /**
+     Get the selected modifiers.
+     */
+    pub fn modifiers(&self) -> impl Iterator<Item = &EffectModifier> {
+        self.modifiers.iter().map(|(_, m)| m)
+    }
+
+    /**
+     Select modifier `name` from mod `group`, replacing whatever was selected from that group before.
+
+     **Returns** `false` if `group` isn't one of the equipment's mod groups or `genre` has no such modifier.
+     */
+    pub fn select_modifier(&mut self, genre: &Genre, group: &str, name: &str) -> bool {
+        if !self.equipment.mod_groups().iter().any(|g| g.eq_ignore_ascii_case(group.trim())) {
+            return false;
+        }
+        match genre.find_modifier(group, name) {
+            Some(m) => {
+                self.unselect_modifier(group);
+                self.modifiers.push((group.trim().to_string(), m.clone()));
+                true
+            },
+            None => false
+        }
+    }
+
+    /**
+     Drop whatever modifier was selected from mod `group`.
+     */
+    pub fn unselect_modifier(&mut self, group: &str) {
+        self.modifiers.retain(|(g, _)| !g.eq_ignore_ascii_case(group.trim()))
+    }
+
+    /**
+     Get cost of a single piece, modifiers applied.
+     */
+    pub fn unit_cost(&self) -> f64 {
+        effect::apply(self.modifiers(), &Target::Cost, self.equipment.cost())
+    }
+
+    /**
+     Get weight of a single piece, modifiers applied.
+     */
+    pub fn unit_weight(&self) -> f64 {
+        effect::apply(self.modifiers(), &Target::Weight, self.equipment.weight().unwrap_or(0.0))
+    }
+
+    /**
+     Get weapon damage, modifiers applied. Non-weapons deal none.
+     */
+    pub fn damage(&self) -> Vec<Damage> {
+        match &self.equipment {
+            Equipment::Weapon(w) => w.damage().iter().map(|d| effect::apply_to_damage(self.modifiers(), d)).collect(),
+            _ => vec![]
+        }
+    }
+
+    /**
+     Get armor DR, modifiers applied.
+     */
+    pub fn dr(&self) -> Option<DamageResistance> {
+        match &self.equipment {
+            Equipment::Armor(a) => a.dr().as_ref().map(|dr| effect::apply_to_dr(self.modifiers(), dr)),
+            _ => None
+        }
+    }
+
+    /**
+     Get armor PD, modifiers applied.
+     */
+    pub fn pd(&self) -> Option<PassiveDefense> {
+        match &self.equipment {
+            Equipment::Armor(a) => a.pd().as_ref().map(|pd| effect::apply_to_pd(self.modifiers(), pd)),
+            _ => None
+        }
+    }
+
+    /**
+     Get skill modifier the selected modifiers give when using this.
+     */
+    pub fn skill_modifier(&self) -> i32 {
+        effect::adds(self.modifiers(), &Target::Skill)
+    }
+
+    /**
+     Get total weight of contents.
+     */
+    pub fn contents_weight(&self) -> f64 {
+        self.contents.iter().filter_map(|p| p.weight()).sum()
+    }
+
+    /**
+     Stow `item` into this possession, if it's a container with room to spare.
+     */
+    pub fn stow(&mut self, item: Possession) -> Result<(), StowError> {
+        match self.equipment.container() {
+            None => Err(StowError::NotAContainer),
+            Some(Container::Liquid(_)) => Err(StowError::LiquidOnly),
+            Some(Container::Wt(capacity)) => {
+                let capacity = *capacity as f64 * self.quantity as f64;
+                let load = self.contents_weight() + item.weight().unwrap_or(0.0);
+                if load > capacity {
+                    Err(StowError::Overfull { capacity, load })
+                } else {
+                    self.contents.push(item);
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+/**
+ Make a spare load (magazine, cell, belt, …) for `weapon`.
+
+ Cells come with their cost and weight; other loads are up to the GM.
+ */
+fn spare(shots: &Shots, weapon: &str) -> Equipment {
+    let (cost, weight) = match shots {
+        Shots::Battery(_, b) => (Some(b.cost()), b.weight()),
+        _ => (None, None)
+    };
+    Equipment::Item(Item {
+        name: shots.spare_name(weapon), notes: None, cost, weight,
+        skill: None, mod_groups: vec![], container: None, spec: Default::default(),
+    })
+}
+
+/**
+ Whatever a `Ch` owns.
+ */
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Inventory {
+    items: Vec<Possession>,
+}
+
+impl Inventory {
+    /**
+     Instantiate an empty [Inventory].
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     Add `quantity` of `equipment`.
+
+     **Returns** the newly added [Possession].
+     */
+    pub fn add(&mut self, equipment: Equipment, quantity: usize) -> &mut Possession {
+        self.items.push(Possession::new(equipment, quantity));
+        self.items.last_mut().unwrap()
+    }
+
+    /**
+     Get all (top level) possessions.
+     */
+    pub fn items(&self) -> &Vec<Possession> {
+        &self.items
+    }
+
+    /**
+     Get all worn possessions.
+     */
+    pub fn worn(&self) -> impl Iterator<Item = &Possession> {
+        self.items.iter().filter(|p| p.worn)
+    }
+
+    /**
+     Wear (or take off) the first possession called `name`.
+
+     **Returns** `false` if there's no such possession.
+     */
+    pub fn wear(&mut self, name: &str, worn: bool) -> bool {
+        match self.items.iter_mut().find(|p| p.name().eq(name)) {
+            Some(p) => { p.worn = worn; true },
+            None => false
+        }
+    }
+
+    /**
+     Stow `quantity` of `equipment` into (the first) possession called `container`.
+     */
+    pub fn stow(&mut self, container: &str, equipment: Equipment, quantity: usize) -> Result<(), StowError> {
+        match self.items.iter_mut().find(|p| p.name().eq(container)) {
+            Some(c) => c.stow(Possession::new(equipment, quantity)),
+            None => Err(StowError::NotFound)
+        }
+    }
+
+    /**
+     Take away `quantity` of (top level) possessions called `name`, stack by stack.
+
+     Whatever was stowed in containers taken away (or in what no longer has room for it) is moved to the top level.
+
+     **Returns** cost of what was taken &ndash; or `None` if there isn't that many.
+     */
+    pub fn take(&mut self, name: &str, quantity: usize) -> Option<f64> {
+        let owned: usize = self.items.iter().filter(|p| p.name().eq(name)).map(|p| p.quantity).sum();
+        if owned < quantity {
+            return None;
+        }
+        let (mut left, mut cost, mut spilled) = (quantity, 0.0, vec![]);
+        for p in self.items.iter_mut().filter(|p| p.name().eq(name)) {
+            if left == 0 {
+                break;
+            }
+            let n = p.quantity.min(left);
+            p.quantity -= n;
+            left -= n;
+            cost += n as f64 * p.unit_cost();
+            let overfull = matches!(p.equipment.container(), Some(Container::Wt(c)) if p.contents_weight() > *c as f64 * p.quantity as f64);
+            if p.quantity == 0 || overfull {
+                spilled.append(&mut p.contents);
+            }
+        }
+        self.items.retain(|p| p.quantity > 0);
+        self.items.extend(spilled);
+        Some(cost)
+    }
+
+    /**
+     Add `quantity` spare loads (magazines, cells, belts, …) or loose rounds for the (first) weapon called `weapon`.
+     */
+    pub fn add_spares(&mut self, weapon: &str, quantity: usize) -> Result<&mut Possession, AmmoError> {
+        let shots = self.items.iter().find(|p| p.name().eq(weapon)).ok_or(AmmoError::NotFound)?
+            .shots().ok_or(AmmoError::NoAmmo)?.clone();
+        Ok(self.add(spare(&shots, weapon), quantity))
+    }
+
+    /**
+     Reload the (first) weapon called `weapon` from spares carried, containers included.
+
+     Loads are swapped whole, loose rounds are loaded one by one as far as they go.
+     Spare cells go by their size, e.g. any "C cell …" fits a `C` cell weapon.
+     The ejected load goes back among the spares with whatever is left in it.
+     */
+    pub fn reload(&mut self, weapon: &str) -> Result<(), AmmoError> {
+        /// Take up to `wanted` non-empty spares, each holding `per_unit` unless it says otherwise.
+        /// Returns the rounds taken.
+        fn take(items: &mut Vec<Possession>, spare: &str, wanted: usize, per_unit: i32) -> (usize, i32) {
+            let (mut taken, mut rounds) = (0, 0);
+            for p in items.iter_mut() {
+                let load = p.ammo.as_ref().map_or(per_unit, |a| a.loaded);
+                if taken < wanted && load > 0 && p.name().to_ascii_lowercase().starts_with(spare) {
+                    let n = p.quantity.min(wanted - taken);
+                    p.quantity -= n;
+                    taken += n;
+                    rounds += n as i32 * load;
+                }
+                if taken < wanted {
+                    let (n, r) = take(&mut p.contents, spare, wanted - taken, per_unit);
+                    taken += n;
+                    rounds += r;
+                }
+            }
+            items.retain(|p| p.quantity > 0 || !p.contents.is_empty());
+            (taken, rounds)
+        }
+
+        let p = self.items.iter().find(|p| p.name().eq(weapon)).ok_or(AmmoError::NotFound)?;
+        let shots = p.shots().ok_or(AmmoError::NoAmmo)?.clone();
+        let ammo = p.ammo.clone().unwrap_or_else(|| shots.full());
+        let room = shots.capacity() + shots.chamber() - ammo.rounds();
+        if room <= 0 || shots.is_unlimited() {
+            return Err(AmmoError::Full);
+        }
+        let spare_name = shots.spare_name(weapon).to_ascii_lowercase();
+        let (taken, rounds) = if shots.is_loose() {
+            take(&mut self.items, &spare_name, room as usize, 1)
+        } else {
+            take(&mut self.items, &spare_name, 1, shots.capacity())
+        };
+        if taken == 0 {
+            return Err(AmmoError::NoSpares);
+        }
+
+        let p = self.items.iter_mut().find(|p| p.name().eq(weapon)).unwrap();
+        let ammo = p.ammo.get_or_insert(ammo);
+        if shots.is_loose() {
+            ammo.load(&shots, rounds);
+        } else {
+            let ejected = ammo.swap_with(&shots, rounds);
+            self.add(spare(&shots, weapon), 1).ammo = Some(Ammo { loaded: ejected, chambered: 0 });
+        }
+        Ok(())
+    }
+
+    /**
+     Get total weight of everything, containers' contents included.
+     */
+    pub fn total_weight(&self) -> f64 {
+        self.items.iter().filter_map(|p| p.weight()).sum()
+    }
+
+    /**
+     Count how many of `name` there are, containers' contents included.
+     */
+    pub fn count(&self, name: &str) -> usize {
+        fn count_in(items: &[Possession], name: &str) -> usize {
+            items.iter()
+                .map(|p| if p.name().eq(name) { p.quantity } else { 0 } + count_in(&p.contents, name))
+                .sum()
+        }
+        count_in(&self.items, name)
+    }
+
+    /**
+     Like [Inventory::take], but containers' contents included &ndash; top level first, then container by container.
+     */
+    pub fn take_anywhere(&mut self, name: &str, quantity: usize) -> Option<f64> {
+        fn take_in(inventory: &mut Inventory, name: &str, wanted: usize) -> f64 {
+            let here = inventory.items.iter().filter(|p| p.name().eq(name)).map(|p| p.quantity).sum::<usize>().min(wanted);
+            let mut cost = inventory.take(name, here).unwrap_or(0.0);
+            let mut left = wanted - here;
+            for p in inventory.items.iter_mut() {
+                if left == 0 {
+                    break;
+                }
+                let mut stowed = Inventory { items: std::mem::take(&mut p.contents) };
+                let n = stowed.count(name).min(left);
+                cost += take_in(&mut stowed, name, n);
+                p.contents = stowed.items;
+                left -= n;
+            }
+            cost
+        }
+        if self.count(name) < quantity {
+            return None;
+        }
+        Some(take_in(self, name, quantity))
+    }
+}
+
+#[cfg(test)]
+mod inventory_tests {
+    use std::collections::HashMap;
+
+    use crate::{context::{Context, ContextPayload}, damage::{Damage, DamageDelivery}, dta::genre::Genre, equipment::{item::{container::Container, Item}, weapon::{melee::Melee, ranged::{ammo::{Ammo, AmmoError}, rof::RoF, shots::{Battery, Shots}, Ranged}, Weapon}, Equipment}, misc::{category::{Category, CategoryPayload}, costly::Costly, named::Named, weighed::Weighed}, modifier::effect::{Effect, EffectModifier, Op, Target}};
+
+    use super::{Inventory, Possession};
+
+    fn sword() -> Equipment {
+        Equipment::Weapon(Weapon::Melee(Melee {
+            name: "Broadsword".to_string(), damage: vec![Damage::Cut(DamageDelivery::Sw(1))], max_damage: None,
+            cost: Some(500.0), weight: Some(3.0), skill: None, notes: None,
+            mod_groups: vec!["Sword Quality".to_string()], acc: None, st_req: None, spec: Default::default(),
+            reach: vec![], parry: Default::default(), two_handed: false,
+        }))
+    }
+
+    fn quality(name: &str, effects: Vec<Effect>) -> CategoryPayload {
+        CategoryPayload::Modifier(EffectModifier { name: name.to_string(), effects, unparsed: vec![] })
+    }
+
+    fn genre() -> Genre {
+        let mut cat = Category::new("Sword Quality");
+        cat.items.insert("Cheap".to_string(), quality("Cheap", vec![
+            Effect { op: Op::Mul(0.4), target: Target::Cost },
+            Effect { op: Op::Add(-1.0), target: Target::Skill },
+        ]));
+        cat.items.insert("Fine".to_string(), quality("Fine", vec![
+            Effect { op: Op::Mul(4.0), target: Target::Cost },
+            Effect { op: Op::Add(1.0), target: Target::Damage },
+        ]));
+        let mut ctx = ContextPayload::new(Context::Modifier);
+        ctx.items.insert(cat.name.clone(), cat);
+        let mut genre = Genre::new();
+        genre.items = HashMap::from([(Context::Modifier, ctx)]);
+        genre
+    }
+
+    #[test]
+    fn modifiers_work() {
+        let genre = genre();
+        let mut p = Possession::new(sword(), 2);
+        assert_eq!(1000.0, p.cost());
+
+        assert!(p.select_modifier(&genre, "Sword Quality", "fine"));
+        assert_eq!(2000.0, p.unit_cost());
+        assert_eq!(4000.0, p.cost());
+        assert_eq!(Some(6.0), p.weight());
+        assert_eq!(vec![Damage::Cut(DamageDelivery::Sw(2))], p.damage());
+
+        // Same group replaces the earlier pick.
+        assert!(p.select_modifier(&genre, "Sword Quality", "Cheap"));
+        assert_eq!(1, p.modifiers.len());
+        assert_eq!(200.0, p.unit_cost());
+        assert_eq!(-1, p.skill_modifier());
+        assert_eq!(vec![Damage::Cut(DamageDelivery::Sw(1))], p.damage());
+
+        assert!(!p.select_modifier(&genre, "Sword Quality", "Legendary"));
+        assert!(!p.select_modifier(&genre, "Clothing Quality", "Fine"));
+        p.unselect_modifier("Sword Quality");
+        assert_eq!(500.0, p.unit_cost());
+    }
+
+    fn gun(name: &str, shots: Shots) -> Equipment {
+        Equipment::Weapon(Weapon::Ranged(Ranged {
+            name: name.to_string(), damage: vec![], max_damage: None,
+            acc: 2, ss: Some(10), rof: Some(RoF::SemiAuto(3)), rcl: Some(-1),
+            min_range: None, half_dmg_range: None, max_range: None, st_req: None, tripod: false,
+            cost: None, weight: None, skill: None, notes: None, shots: Some(shots), mod_groups: vec![], spec: Default::default(),
+        }))
+    }
+
+    #[test]
+    fn reload_works() {
+        let mut inv = Inventory::new();
+        inv.add(gun("Pistol", Shots::MagazinePlus(15, 1)), 1);
+        inv.add(gun("Blaster", Shots::Battery(12, Battery::C)), 1);
+        inv.add(gun("Shotgun", Shots::DualBarrel), 1);
+        inv.add(Equipment::Item(Item {
+            name: "Backpack".to_string(), notes: None, cost: None, weight: Some(3.0),
+            skill: None, mod_groups: vec![], container: Some(Container::Wt(40)), spec: Default::default(),
+        }), 1);
+        inv.add_spares("Pistol", 1).unwrap();
+        assert_eq!(Err(AmmoError::NoAmmo), inv.add_spares("Backpack", 1).map(|_| ()));
+
+        assert_eq!(Err(AmmoError::Full), inv.reload("Pistol"));
+        let pistol = |inv: &mut Inventory, rounds| inv.items.iter_mut().find(|p| p.name() == "Pistol").unwrap().fire(rounds);
+        assert_eq!(Ok(10), pistol(&mut inv, 10));
+        assert_eq!(Ok(()), inv.reload("Pistol"));
+        // The ejected magazine is kept, with what's left in it.
+        assert_eq!(1, inv.count("Pistol magazine"));
+        assert_eq!(Some(Ammo { loaded: 5, chambered: 0 }), inv.items.last().unwrap().ammo);
+        assert_eq!(Some(Ammo { loaded: 15, chambered: 1 }), inv.items[0].ammo);
+        assert_eq!(Ok(16), pistol(&mut inv, 20));
+        // .. and goes back in as it is.
+        assert_eq!(Ok(()), inv.reload("Pistol"));
+        assert_eq!(Some(Ammo { loaded: 4, chambered: 1 }), inv.items[0].ammo);
+        assert_eq!(Some(Ammo { loaded: 0, chambered: 0 }), inv.items.last().unwrap().ammo);
+        assert_eq!(Ok(5), pistol(&mut inv, 20));
+        assert_eq!(Err(AmmoError::NoSpares), inv.reload("Pistol"));
+
+        // Any C cell fits, even one stowed in the backpack.
+        inv.stow("Backpack", Equipment::Item(Item {
+            name: "C cell (TL8)".to_string(), notes: None, cost: Some(100.0), weight: Some(0.5),
+            skill: None, mod_groups: vec![], container: None, spec: Default::default(),
+        }), 2).unwrap();
+        inv.items[1].fire(12).unwrap();
+        assert_eq!(Ok(()), inv.reload("Blaster"));
+        assert_eq!(1, inv.count("C cell (TL8)"));
+        assert_eq!(Some(Ammo { loaded: 0, chambered: 0 }), inv.items.last().unwrap().ammo);
+
+        inv.items[2].fire(2).unwrap();
+        inv.add_spares("Shotgun", 1).unwrap();
+        assert_eq!(Ok(()), inv.reload("Shotgun"));
+        assert_eq!(Some(1), inv.items[2].ammo.as_ref().map(|a| a.rounds()));
+        assert_eq!(Err(AmmoError::NotFound), inv.reload("Cannon"));
+
+        let cell = inv.add_spares("Blaster", 2).unwrap();
+        assert_eq!("C cell", cell.name());
+        assert_eq!(200.0, cell.cost());
+    }
+}
@@ -16,4 +16,16 @@ pub mod dta;
 pub mod context;
 pub mod equipment;
 pub mod damage;
+pub mod dice;
 pub mod skill;
+pub mod inventory;
+pub mod hit_location;
+pub mod encumbrance;
+pub mod shop;
+pub mod secondary;
+pub mod advancement;
+pub mod generator;
+pub mod campaign;
+pub mod template;
+#[cfg(test)]
+pub(crate) mod fixtures;
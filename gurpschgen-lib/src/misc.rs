@@ -10,3 +10,4 @@ pub mod st_req;
 pub mod damaged;
 pub mod category;
 pub mod tl;
+pub mod specced;
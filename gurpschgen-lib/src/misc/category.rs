@@ -2,17 +2,32 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{adq::Adq, dta::genre::Genre, equipment::Equipment, skill::Skill};
+use crate::{adq::Adq, dta::genre::Genre, equipment::Equipment, modifier::effect::EffectModifier, skill::Skill};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Category {
     pub name: String,
     pub items: HashMap<String, CategoryPayload>,
+    /// Comments preceding the category (or its first item), e.g. column headers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+    /// Comments preceding individual items, keyed by item name.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub item_notes: HashMap<String, Vec<String>>,
 }
 
 impl Category {
     pub fn new(name: &str) -> Self {
-        Category { name: name.to_string(), items: HashMap::new() }
+        Category { name: name.to_string(), items: HashMap::new(), notes: vec![], item_notes: HashMap::new() }
+    }
+
+    /**
+     Merge `other` into `self`; `other`'s items (and their notes) win on name clashes.
+     */
+    pub fn merge(&mut self, other: Category) {
+        self.items.extend(other.items);
+        self.notes.extend(other.notes);
+        self.item_notes.extend(other.item_notes);
     }
 }
 
@@ -24,7 +39,7 @@ pub enum CategoryPayload {
     Disadvantage(Adq),
     Equipment(Equipment),
     Genre(Genre),
-    Modifier(String),
+    Modifier(EffectModifier),
     Package(Adq),
     Quirk(String),
     Skill(Skill),
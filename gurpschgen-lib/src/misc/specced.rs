@@ -0,0 +1,11 @@
+use crate::equipment::spec::Spec;
+
+/**
+ A trait for anything with (structured) [Spec].
+ */
+pub trait Specced {
+    /**
+     Get the spec.
+     */
+    fn spec(&self) -> &Spec;
+}
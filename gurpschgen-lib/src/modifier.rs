@@ -1,10 +1,18 @@
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub mod effect;
+pub mod race;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum Modifier {
+    /// Cost reduction for large size; `-SM`, i.e. `I(-2)` for SM +2.
     Size,
     NoFineManipulators,
+    /// Plain cost multiplier, `F(x)`, e.g. as per racial template.
+    CostMultiplier,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum ModifierValue {
     I(i32),
     F(f64),
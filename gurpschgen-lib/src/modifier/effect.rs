@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{damage::{Damage, DamageDelivery, DamageResistance, DamageType, PassiveDefense}, misc::named::Named};
+
+/**
+ How an [Effect] changes its target.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum Op {
+    /// `*x`
+    Mul(f64),
+    /// `/x`
+    Div(f64),
+    /// `+x` or `-x`
+    Add(f64),
+}
+
+/**
+ What an [Effect] changes.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Target {
+    Cost,
+    PointCost,
+    Weight,
+    Damage,
+    DR,
+    PD,
+    Skill,
+    Other(String),
+}
+
+/**
+ A single effect of a modifier, e.g. `*4 Cost` or `+1 Damage`.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Effect {
+    pub op: Op,
+    pub target: Target,
+}
+
+/**
+ A named set of [Effect]s &ndash; an entry of a DTA `type modifier` category (a "mod group").
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "EffectModifierRepr")]
+pub struct EffectModifier {
+    pub name: String,
+    pub effects: Vec<Effect>,
+    /// Whatever the DTA converter couldn't make sense of.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unparsed: Vec<String>,
+}
+
+/**
+ Older JSON has modifiers as bare names.
+ */
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EffectModifierRepr {
+    Name(String),
+    Full {
+        name: String,
+        #[serde(default)]
+        effects: Vec<Effect>,
+        #[serde(default)]
+        unparsed: Vec<String>,
+    },
+}
+
+impl From<EffectModifierRepr> for EffectModifier {
+    fn from(value: EffectModifierRepr) -> Self {
+        match value {
+            EffectModifierRepr::Name(name) => Self::new(&name),
+            EffectModifierRepr::Full { name, effects, unparsed } => Self { name, effects, unparsed },
+        }
+    }
+}
+
+impl Named for EffectModifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl EffectModifier {
+    /**
+     Instantiate an effect-less [EffectModifier].
+     */
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), effects: vec![], unparsed: vec![] }
+    }
+}
+
+/**
+ Apply all effects of `modifiers` targeting `target` to `value`.
+
+ Adds are applied first, then multipliers/divisors.
+ */
+pub fn apply<'a, I>(modifiers: I, target: &Target, value: f64) -> f64
+where I: IntoIterator<Item = &'a EffectModifier>
+{
+    let effects: Vec<&Effect> = modifiers.into_iter()
+        .flat_map(|m| m.effects.iter())
+        .filter(|e| e.target.eq(target))
+        .collect();
+    let added = effects.iter().fold(value, |v, e| match e.op {
+        Op::Add(x) => v + x,
+        _ => v
+    });
+    effects.iter().fold(added, |v, e| match e.op {
+        Op::Mul(x) => v * x,
+        Op::Div(x) if x != 0.0 => v / x,
+        _ => v
+    })
+}
+
+/**
+ Get the total (integer) adds `modifiers` give to `target`.
+ */
+pub fn adds<'a, I>(modifiers: I, target: &Target) -> i32
+where I: IntoIterator<Item = &'a EffectModifier>
+{
+    apply(modifiers, target, 0.0).round() as i32
+}
+
+/**
+ Add `adds` to damage `delivery`, if it's additive at all.
+ */
+fn add_to_delivery(delivery: &DamageDelivery, adds: i32) -> DamageDelivery {
+    match delivery {
+        DamageDelivery::Dice(d, a) => DamageDelivery::Dice(*d, a + adds),
+        DamageDelivery::DiceMul(d, a, m) => DamageDelivery::DiceMul(*d, a + adds, *m),
+        DamageDelivery::Flat(x) => DamageDelivery::Flat(x + adds),
+        DamageDelivery::Sw(a) => DamageDelivery::Sw(a + adds),
+        DamageDelivery::Thr(a) => DamageDelivery::Thr(a + adds),
+        x => x.clone()
+    }
+}
+
+/**
+ Apply `Damage` effects to `damage`.
+ */
+pub fn apply_to_damage<'a, I>(modifiers: I, damage: &Damage) -> Damage
+where I: IntoIterator<Item = &'a EffectModifier>
+{
+    Damage::new(damage.damage_type(), add_to_delivery(damage.delivery(), adds(modifiers, &Target::Damage)))
+}
+
+/**
+ Apply `DR` effects to `dr`.
+ */
+pub fn apply_to_dr<'a, I>(modifiers: I, dr: &DamageResistance) -> DamageResistance
+where I: IntoIterator<Item = &'a EffectModifier>
+{
+    let n = adds(modifiers, &Target::DR);
+    match dr {
+        DamageResistance::All(v) => DamageResistance::All(v + n),
+        _ => DamageType::ALL.iter().fold(dr.clone(), |dr, t| { let v = dr.against(*t) + n; dr.vs(*t, v) })
+    }
+}
+
+/**
+ Apply `PD` effects to `pd`.
+ */
+pub fn apply_to_pd<'a, I>(modifiers: I, pd: &PassiveDefense) -> PassiveDefense
+where I: IntoIterator<Item = &'a EffectModifier>
+{
+    let n = adds(modifiers, &Target::PD);
+    match pd {
+        PassiveDefense::All(v) => PassiveDefense::All(v + n),
+        _ => DamageType::ALL.iter().fold(pd.clone(), |pd, t| { let v = pd.against(*t) + n; pd.vs(*t, v) })
+    }
+}
+
+#[cfg(test)]
+mod effect_tests {
+    use crate::damage::{Damage, DamageDelivery};
+
+    use super::{apply, apply_to_damage, Effect, EffectModifier, Op, Target};
+
+    fn fine() -> EffectModifier {
+        EffectModifier {
+            name: "Fine".to_string(),
+            effects: vec![
+                Effect { op: Op::Mul(4.0), target: Target::Cost },
+                Effect { op: Op::Add(1.0), target: Target::Damage },
+            ],
+            unparsed: vec![],
+        }
+    }
+
+    #[test]
+    fn effects_work() {
+        let m = [fine()];
+        assert_eq!(2000.0, apply(&m, &Target::Cost, 500.0));
+        assert_eq!(3.0, apply(&m, &Target::Weight, 3.0));
+        assert_eq!(Damage::Cut(DamageDelivery::Sw(2)), apply_to_damage(&m, &Damage::Cut(DamageDelivery::Sw(1))));
+    }
+
+    #[test]
+    fn bare_names_deserialize() {
+        let m: EffectModifier = serde_json::from_str(r#""Fine""#).unwrap();
+        assert_eq!(EffectModifier::new("Fine"), m);
+        let m: EffectModifier = serde_json::from_str(&serde_json::to_string(&fine()).unwrap()).unwrap();
+        assert_eq!(fine(), m);
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{adq::Adq, attrib::AttributeType, edition::GurpsEd, equipment::weapon::melee::reach::Reach, misc::named::Named};
+
+use super::{Modifier, ModifierValue};
+
+/// Traits (as given by race packages) which imply No Fine Manipulators.
+const NO_FINE_MANIPULATORS: [&str; 2] = ["No Fine Manipulators", "No Manipulators"];
+
+/**
+ Racial template &ndash; attribute bonuses, limits, size and cost multipliers of a race.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct RacialTemplate {
+    pub name: String,
+    /// Racial attribute bonuses (or penalties), applied to base values.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub bonuses: HashMap<AttributeType, i32>,
+    /// Explicit attribute minima; `1` by default.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub minima: HashMap<AttributeType, i32>,
+    /// Explicit attribute maxima; genre's max + racial bonus by default.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub maxima: HashMap<AttributeType, i32>,
+    /// Size Modifier.
+    #[serde(default)]
+    pub sm: i32,
+    /// Attribute cost multipliers, e.g. `IQ → 0.5` for a race with severe learning limitations.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub cost_multipliers: HashMap<AttributeType, f64>,
+    #[serde(default)]
+    pub no_fine_manipulators: bool,
+}
+
+impl RacialTemplate {
+    /**
+     Instantiate a blank template.
+     */
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), ..Default::default() }
+    }
+
+    pub fn with_bonus(mut self, attrib: AttributeType, bonus: i32) -> Self {
+        self.bonuses.insert(attrib, bonus);
+        self
+    }
+
+    pub fn with_minimum(mut self, attrib: AttributeType, min: i32) -> Self {
+        self.minima.insert(attrib, min);
+        self
+    }
+
+    pub fn with_maximum(mut self, attrib: AttributeType, max: i32) -> Self {
+        self.maxima.insert(attrib, max);
+        self
+    }
+
+    pub fn with_sm(mut self, sm: i32) -> Self {
+        self.sm = sm;
+        self
+    }
+
+    pub fn with_cost_multiplier(mut self, attrib: AttributeType, multiplier: f64) -> Self {
+        self.cost_multipliers.insert(attrib, multiplier);
+        self
+    }
+
+    pub fn with_no_fine_manipulators(mut self) -> Self {
+        self.no_fine_manipulators = true;
+        self
+    }
+
+    /**
+     Build a template out of a race package/[Adq].
+
+     Picks up `"±N ST|DX|IQ|HT Bonus"` and `"±N SM Bonus"` from bonus mods, Gigantism/Dwarfism
+     (SM ±1) and No (Fine) Manipulators from what the package gives; anything else is ignored.
+     */
+    pub fn from_adq(adq: &Adq) -> Self {
+        let mut template = Self::new(adq.name());
+        for bonus in adq.bonus_mods() {
+            let tokens = bonus.split_whitespace().collect::<Vec<_>>();
+            let [value, what, "Bonus"] = tokens[..] else { continue };
+            let Ok(value) = value.trim_start_matches('+').parse::<i32>() else { continue };
+            match what {
+                "ST" => template.bonuses.insert(AttributeType::ST, value),
+                "DX" => template.bonuses.insert(AttributeType::DX, value),
+                "IQ" => template.bonuses.insert(AttributeType::IQ, value),
+                "HT" => template.bonuses.insert(AttributeType::HT, value),
+                "SM" => { template.sm += value; None },
+                _ => None
+            };
+        }
+        for given in adq.gives() {
+            let given = given.split('@').next().unwrap_or_default();
+            match given {
+                "Gigantism" => template.sm += 1,
+                "Dwarfism" => template.sm -= 1,
+                _ if NO_FINE_MANIPULATORS.contains(&given) => template.no_fine_manipulators = true,
+                _ => ()
+            }
+        }
+        template
+    }
+
+    /**
+     Get racial bonus of `attrib`.
+     */
+    pub fn bonus(&self, attrib: AttributeType) -> i32 {
+        *self.bonuses.get(&attrib).unwrap_or(&0)
+    }
+
+    /**
+     Get the allowed `(min, max)` of `attrib`, given genre's max attribute value.
+     */
+    pub fn bounds(&self, attrib: AttributeType, genre_max: i32) -> (i32, i32) {
+        (
+            *self.minima.get(&attrib).unwrap_or(&1),
+            *self.maxima.get(&attrib).unwrap_or(&(genre_max + self.bonus(attrib))),
+        )
+    }
+
+    /**
+     Get the [Modifier]s the template imposes on `attrib`.
+     */
+    pub fn attribute_modifiers(&self, attrib: AttributeType) -> Vec<(Modifier, Option<ModifierValue>)> {
+        let mut modifiers = vec![];
+        if attrib == AttributeType::ST {
+            if self.sm > 0 {
+                modifiers.push((Modifier::Size, Some(ModifierValue::I(-self.sm))));
+            }
+            if self.no_fine_manipulators {
+                modifiers.push((Modifier::NoFineManipulators, None));
+            }
+        }
+        if let Some(m) = self.cost_multipliers.get(&attrib) {
+            modifiers.push((Modifier::CostMultiplier, Some(ModifierValue::F(*m))));
+        }
+        modifiers
+    }
+
+    /**
+     Get cost multiplier for extra HP. In 4e, HP (like ST) gets cheaper with SM and lack of fine manipulators.
+     */
+    pub fn hp_cost_multiplier(&self, edition: &GurpsEd) -> f64 {
+        match edition {
+            GurpsEd::Ed3 => 1.0,
+            GurpsEd::Ed4 => {
+                let mut m = 1.0 - 0.1 * self.sm.clamp(0, 8) as f64;
+                if self.no_fine_manipulators {
+                    m *= 0.6
+                }
+                m
+            }
+        }
+    }
+
+    /**
+     Scale a weapon's `reach` to the race's size; only SM +1 and up make a difference.
+
+     Reach grows along with linear size: SM 0 is 2 yds, +1 is 3, +2 is 5, +3 is 7, +4 is 10, …
+     */
+    pub fn scale_reach(&self, reach: Reach) -> Reach {
+        const STEPS: [f64; 6] = [1.0, 1.5, 2.0, 3.0, 5.0, 7.0];
+        match reach {
+            Reach::Yards(r) if self.sm > 0 => {
+                let step = (self.sm + 2) as usize;
+                let size = STEPS[step % 6] * 10f64.powi((step / 6) as i32);
+                Reach::Yards((r as f64 * size / 2.0).round() as i32)
+            },
+            _ => reach
+        }
+    }
+}
+
+impl Named for RacialTemplate {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod race_tests {
+    use crate::{adq::Adq, attrib::AttributeType, edition::GurpsEd, equipment::weapon::melee::reach::Reach, modifier::{Modifier, ModifierValue}};
+
+    use super::RacialTemplate;
+
+    #[test]
+    fn from_adq_works() {
+        let adq = Adq {
+            name: "Race: Ogre".to_string(),
+            initial_cost: 20,
+            cost_increment: 0,
+            level: 1,
+            max_level: 1,
+            bonus_mods: vec!["+4 ST Bonus".into(), "-2 IQ Bonus".into(), "+1 SM Bonus".into(), "+1 Savoir Faire Bonus".into()],
+            given: vec!["Gigantism".into(), "No Fine Manipulators".into(), "Night Vision@2".into()],
+            mod_groups: vec![],
+            equipment_given: vec![],
+            cash_cost: 0.0,
+        };
+        let t = RacialTemplate::from_adq(&adq);
+        assert_eq!(4, t.bonus(AttributeType::ST));
+        assert_eq!(-2, t.bonus(AttributeType::IQ));
+        assert_eq!(0, t.bonus(AttributeType::DX));
+        assert_eq!(2, t.sm);
+        assert!(t.no_fine_manipulators);
+        assert_eq!((1, 24), t.bounds(AttributeType::ST, 20));
+    }
+
+    #[test]
+    fn size_works() {
+        let t = RacialTemplate::new("Giant")
+            .with_sm(2)
+            .with_maximum(AttributeType::DX, 14)
+            .with_cost_multiplier(AttributeType::IQ, 0.5);
+        assert_eq!((1, 14), t.bounds(AttributeType::DX, 20));
+        assert_eq!(Reach::Yards(3), t.scale_reach(Reach::Yards(1)));
+        assert_eq!(Reach::Yards(5), t.scale_reach(Reach::Yards(2)));
+        assert_eq!(Reach::C, t.scale_reach(Reach::C));
+        assert_eq!(Reach::Yards(1), RacialTemplate::new("Dwarf").with_sm(-1).scale_reach(Reach::Yards(1)));
+        assert_eq!(0.8, t.hp_cost_multiplier(&GurpsEd::Ed4));
+        assert_eq!(1.0, t.hp_cost_multiplier(&GurpsEd::Ed3));
+        assert_eq!(vec![(Modifier::Size, Some(ModifierValue::I(-2)))], t.attribute_modifiers(AttributeType::ST));
+        assert_eq!(vec![(Modifier::CostMultiplier, Some(ModifierValue::F(0.5)))], t.attribute_modifiers(AttributeType::IQ));
+    }
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::edition::GurpsEd;
+
+/**
+ Secondary characteristics which can be bought up (or down) on their own.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Secondary {
+    HP,
+    Will,
+    Per,
+    FP,
+    /// In quarters, i.e. +1 is +0.25 Basic Speed.
+    Speed,
+    Move,
+}
+
+/**
+ Reasons why a secondary characteristic can't be bought (or sold).
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum PurchaseError {
+    /// Not a separate trait in the edition.
+    NotInEdition,
+    /// Must be bought in steps of this size.
+    Step(i32),
+    /// Result would fall outside allowed (`min`..=`max` extra levels).
+    OutOfBounds { min: i32, max: i32 },
+}
+
+impl Secondary {
+    /// All of them, in the customary order.
+    pub const ALL: [Secondary; 6] = [Self::HP, Self::Will, Self::Per, Self::FP, Self::Speed, Self::Move];
+
+    /**
+     Get point cost per level &ndash; or `None` if not purchasable in `edition`.
+
+     3e: Extra Hit Points/Fatigue and +1 Basic Speed (25 pts, i.e. whole points only).
+     */
+    pub fn price(&self, edition: &GurpsEd) -> Option<f64> {
+        match edition {
+            GurpsEd::Ed3 => match self {
+                Self::HP => Some(5.0),
+                Self::FP => Some(3.0),
+                Self::Speed => Some(6.25),
+                Self::Will |
+                Self::Per |
+                Self::Move => None,
+            },
+            GurpsEd::Ed4 => Some(match self {
+                Self::HP => 2.0,
+                Self::FP => 3.0,
+                Self::Will |
+                Self::Per |
+                Self::Speed |
+                Self::Move => 5.0,
+            }),
+        }
+    }
+
+    /**
+     Get the step size levels have to be bought in.
+     */
+    pub fn step(&self, edition: &GurpsEd) -> i32 {
+        match (edition, self) {
+            (GurpsEd::Ed3, Self::Speed) => 4,
+            _ => 1
+        }
+    }
+}
@@ -0,0 +1,229 @@
+use std::ops::RangeInclusive;
+
+use crate::{ch::Ch, config::Config, context::Context, dta::genre::Genre, equipment::Equipment, inventory::Possession, misc::{category::CategoryPayload, costly::Costly, named::Named, specced::Specced, tl::TL}};
+
+/// Default fraction of (modified) cost paid back on resale.
+const DEFAULT_RESALE: f64 = 0.5;
+
+/**
+ Reasons why a deal falls through.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShopError {
+    /// No such equipment in the genre.
+    NotFound,
+    /// It exists, but isn't for sale here (TL/LC).
+    NotAvailable,
+    /// Not enough cash; what it'd `cost` vs. `cash` at hand.
+    CantAfford { cost: f64, cash: f64 },
+    /// Can't sell what one doesn't have (enough of).
+    NotOwned,
+}
+
+/**
+ An equipment shop over a [Genre]'s equipment, stocked per TL range and legality class.
+ */
+pub struct Shop<'a> {
+    genre: &'a Genre,
+    tl: RangeInclusive<i32>,
+    min_lc: Option<i32>,
+    resale: f64,
+    campaign: Option<&'a Config>,
+}
+
+impl<'a> Shop<'a> {
+    /**
+     Open a shop stocking anything up to `genre`'s (max) TL, regardless of legality.
+     */
+    pub fn new(genre: &'a Genre) -> Self {
+        let max_tl = match genre.tl {
+            TL::Exact(x) => x,
+            TL::About { max, .. } => max,
+        };
+        Self { genre, tl: 0..=max_tl, min_lc: None, resale: DEFAULT_RESALE, campaign: None }
+    }
+
+    /**
+     Stock only equipment within `tl` range.
+     */
+    pub fn with_tl(mut self, tl: RangeInclusive<i32>) -> Self {
+        self.tl = tl;
+        self
+    }
+
+    /**
+     Stock only equipment of legality class `lc` or higher (i.e. as legal or more so).
+     */
+    pub fn with_lc(mut self, lc: i32) -> Self {
+        self.min_lc = Some(lc);
+        self
+    }
+
+    /**
+     Stock only what `campaign` allows, up to its TL.
+     */
+    pub fn with_campaign(mut self, campaign: &'a Config) -> Self {
+        self.tl = *self.tl.start()..=campaign.tl;
+        self.campaign = Some(campaign);
+        self
+    }
+
+    /**
+     Set the fraction of cost paid back on resale.
+     */
+    pub fn with_resale(mut self, resale: f64) -> Self {
+        self.resale = resale.max(0.0);
+        self
+    }
+
+    /**
+     See if `equipment` is for sale here. Equipment without TL/LC info always is, unless the campaign forbids it.
+     */
+    pub fn stocks(&self, equipment: &Equipment) -> bool {
+        let spec = equipment.spec();
+        self.campaign.is_none_or(|c| c.is_allowed(equipment.name()))
+            && spec.tl.is_none_or(|tl| self.tl.contains(&tl))
+            && self.min_lc.is_none_or(|min| spec.lc.is_none_or(|lc| lc >= min))
+    }
+
+    /**
+     Browse equipment for sale, optionally of one `category` only.
+
+     **Returns** category name &amp; equipment pairs, sorted by category and name.
+     */
+    pub fn browse(&self, category: Option<&str>) -> Vec<(&'a str, &'a Equipment)> {
+        let Some(ctx) = self.genre.items.get(&Context::Equipment) else { return vec![] };
+        let mut wares: Vec<_> = ctx.items.iter()
+            .filter(|(name, _)| category.is_none_or(|c| c.eq_ignore_ascii_case(name)))
+            .flat_map(|(name, cat)| cat.items.values().filter_map(move |p| match p {
+                CategoryPayload::Equipment(e) if self.stocks(e) => Some((name.as_str(), e)),
+                _ => None
+            }))
+            .collect();
+        wares.sort_by(|a, b| a.0.cmp(b.0).then_with(|| a.1.name().cmp(b.1.name())));
+        wares
+    }
+
+    /**
+     Buy `quantity` of `name` into `ch`'s inventory, paying from `ch`'s cash.
+     */
+    pub fn buy<'c>(&self, ch: &'c mut Ch, name: &str, quantity: usize) -> Result<&'c mut Possession, ShopError> {
+        let equipment = self.genre.find_equipment(name).ok_or(ShopError::NotFound)?;
+        if !self.stocks(equipment) {
+            return Err(ShopError::NotAvailable);
+        }
+        let cost = equipment.cost() * quantity as f64;
+        if cost > ch.cash {
+            return Err(ShopError::CantAfford { cost, cash: ch.cash });
+        }
+        ch.cash -= cost;
+        Ok(ch.inventory.add(equipment.clone(), quantity))
+    }
+
+    /**
+     Sell `quantity` of (top level) possession `name` off `ch`'s inventory.
+
+     Containers' contents aren't sold along; they're kept (at the top level).
+
+     **Returns** cash received.
+     */
+    pub fn sell(&self, ch: &mut Ch, name: &str, quantity: usize) -> Result<f64, ShopError> {
+        let cost = ch.inventory.take(name, quantity).ok_or(ShopError::NotOwned)?;
+        let cash = cost * self.resale;
+        ch.cash += cash;
+        Ok(cash)
+    }
+}
+
+#[cfg(test)]
+mod shop_tests {
+    use std::collections::HashMap;
+
+    use crate::{ch::Ch, config::Config, context::{Context, ContextPayload}, dta::genre::Genre, edition::GurpsEd, equipment::{item::{container::Container, Item}, spec::Spec, Equipment}, misc::{category::{Category, CategoryPayload}, named::Named, tl::TL}};
+
+    use super::{Shop, ShopError};
+
+    fn ware(name: &str, cost: f64, tl: Option<i32>, lc: Option<i32>) -> CategoryPayload {
+        CategoryPayload::Equipment(Equipment::Item(Item {
+            name: name.to_string(), notes: None, cost: Some(cost), weight: Some(1.0),
+            skill: None, mod_groups: vec![], container: None, spec: Spec { tl, lc, ..Default::default() }.into(),
+        }))
+    }
+
+    fn genre() -> Genre {
+        let mut genre = Genre::new();
+        genre.tl = TL::About { default: 7, min: 6, max: 8 };
+        let mut ctx = ContextPayload::new(Context::Equipment);
+        for (cat, wares) in [
+            ("Gear", vec![ware("Rope", 10.0, None, None), ware("Radio", 200.0, Some(7), Some(6)), ware("Comm", 50.0, Some(9), None)]),
+            ("Weapons", vec![ware("Rifle", 500.0, Some(7), Some(2))]),
+        ] {
+            let mut c = Category::new(cat);
+            for w in wares {
+                if let CategoryPayload::Equipment(e) = &w {
+                    c.items.insert(e.name().to_string(), w.clone());
+                }
+            }
+            ctx.items.insert(cat.to_string(), c);
+        }
+        genre.items = HashMap::from([(Context::Equipment, ctx)]);
+        genre
+    }
+
+    #[test]
+    fn browsing_works() {
+        let genre = genre();
+        let names = |shop: &Shop, cat| shop.browse(cat).iter().map(|(_, e)| e.name().to_string()).collect::<Vec<_>>();
+        assert_eq!(vec!["Radio", "Rope", "Rifle"], names(&Shop::new(&genre), None));
+        assert_eq!(vec!["Radio", "Rope"], names(&Shop::new(&genre).with_lc(3), None));
+        assert_eq!(vec!["Rifle"], names(&Shop::new(&genre), Some("weapons")));
+        assert_eq!(vec!["Comm", "Radio", "Rope"], names(&Shop::new(&genre).with_tl(7..=9), Some("Gear")));
+        let mut campaign = Config::default_tl8(GurpsEd::Ed4);
+        campaign.tl = 9;
+        campaign.forbidden.insert("rifle".to_string());
+        assert_eq!(vec!["Comm", "Radio", "Rope"], names(&Shop::new(&genre).with_campaign(&campaign), None));
+    }
+
+    #[test]
+    fn buying_and_selling_works() {
+        let genre = genre();
+        let shop = Shop::new(&genre).with_lc(2).with_resale(0.4);
+        let mut ch = Ch::new("Nameless");
+        ch.cash = 1000.0;
+        assert!(shop.buy(&mut ch, "Rifle", 1).is_ok());
+        assert_eq!(500.0, ch.cash);
+        assert_eq!(Err(ShopError::CantAfford { cost: 600.0, cash: 500.0 }), shop.buy(&mut ch, "Radio", 3).map(|_| ()));
+        assert_eq!(Err(ShopError::NotAvailable), shop.buy(&mut ch, "Comm", 1).map(|_| ()));
+        assert_eq!(Err(ShopError::NotFound), shop.buy(&mut ch, "Phaser", 1).map(|_| ()));
+        shop.buy(&mut ch, "rope", 5).unwrap();
+        assert_eq!(450.0, ch.cash);
+
+        assert_eq!(Ok(8.0), shop.sell(&mut ch, "Rope", 2));
+        assert_eq!(3, ch.inventory.count("Rope"));
+        assert_eq!(Err(ShopError::NotOwned), shop.sell(&mut ch, "Rope", 4));
+        assert_eq!(Ok(200.0), shop.sell(&mut ch, "Rifle", 1));
+        assert_eq!(0, ch.inventory.count("Rifle"));
+        assert_eq!(658.0, ch.cash);
+
+        // Stacks are sold off one after another.
+        shop.buy(&mut ch, "Rope", 2).unwrap();
+        assert_eq!(Ok(16.0), shop.sell(&mut ch, "Rope", 4));
+        assert_eq!(1, ch.inventory.count("Rope"));
+    }
+
+    #[test]
+    fn selling_containers_works() {
+        let genre = genre();
+        let shop = Shop::new(&genre);
+        let mut ch = Ch::new("Nameless");
+        ch.inventory.add(Equipment::Item(Item {
+            name: "Backpack".to_string(), notes: None, cost: Some(60.0), weight: Some(3.0),
+            skill: None, mod_groups: vec![], container: Some(Container::Wt(40)), spec: Default::default(),
+        }), 1);
+        ch.inventory.stow("Backpack", genre.find_equipment("Rope").unwrap().clone(), 2).unwrap();
+        assert_eq!(Ok(30.0), shop.sell(&mut ch, "Backpack", 1));
+        assert_eq!(0, ch.inventory.count("Backpack"));
+        assert_eq!(2, ch.inventory.count("Rope"));
+        assert_eq!(Some(2), ch.inventory.items().iter().find(|p| p.name() == "Rope").map(|p| p.quantity));
+    }
+}
@@ -2,6 +2,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::{config::Config, edition::GurpsEd, misc::{costly::Costly, named::Named}};
 
+/// Highest rank tried when looking for one matching a skill level.
+const MAX_RANK: usize = 50;
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Stat {
     DX, HT, IQ, ST
@@ -81,6 +84,25 @@ impl Named for Skill {
     }
 }
 
+impl Skill {
+    /**
+     Find the lowest rank (up to [MAX_RANK]) at which `level` of the skill gets to (at least) `target`.
+
+     **Returns** the rank along with the level it gets to &ndash; or `None` if no rank does, or there's no telling.
+     */
+    pub(crate) fn rank_reaching(&self, target: i32, level: impl Fn(&Skill) -> Option<i32>) -> Option<(usize, i32)> {
+        let mut skill = self.clone();
+        for rank in 1..=MAX_RANK {
+            skill.rank = rank;
+            match level(&skill)? {
+                l if l >= target => return Some((rank, l)),
+                _ => ()
+            }
+        }
+        None
+    }
+}
+
 impl Costly for Skill {
     fn cost(&self) -> f64 {
         match self.rank {
@@ -93,6 +115,9 @@ impl Costly for Skill {
 }
 
 pub trait SkillLevel {
+    /**
+     Get level relative to the base stat &ndash; or `None` if there's no telling, e.g. for Specials.
+     */
     fn level(&self, config: &Config) -> Option<i32>;
 }
 
@@ -122,11 +147,34 @@ impl SkillLevel for Skill {
                         n => ((n as i32) - 4).into()
                     },
 
-                    DifficultyRating::S => todo!("Ed3 x/S")
+                    // Specials' levels are up to the skill itself.
+                    DifficultyRating::S => None
                 },
 
-                SkillRoot::MA { diff: d} => todo!("Base::MA(d)"),
-                SkillRoot::P { diff: d, ..} => todo!("Base::P(_,d)")
+                SkillRoot::MA { diff: d} |
+                SkillRoot::P { diff: d, ..} => match d {
+                    DifficultyRating::E => (match self.rank {
+                        ..=0 => -4,
+                        n => (n as i32) - 1
+                    }).into(),
+
+                    DifficultyRating::A => (match self.rank {
+                        ..=0 => -5,
+                        n => (n as i32) - 2
+                    }).into(),
+
+                    DifficultyRating::H => (match self.rank {
+                        ..=0 => -6,
+                        n => (n as i32) - 3
+                    }).into(),
+
+                    DifficultyRating::VH => (match self.rank {
+                        ..=0 => -6,
+                        n => (n as i32) - 4
+                    }).into(),
+
+                    DifficultyRating::S => None
+                }
             },
 
             GurpsEd::Ed4 => match &self.base {
@@ -154,9 +202,26 @@ impl SkillLevel for Skill {
                         n => ((n as i32) - 4).into(),
                     },
 
-                    DifficultyRating::S => todo!("Ed4 x/S")
+                    DifficultyRating::S => None
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod skill_tests {
+    use crate::{config::Config, edition::GurpsEd, fixtures::skill};
+
+    use super::{DifficultyRating, Skill, SkillLevel, SkillRoot, Stat};
+
+    #[test]
+    fn specials_have_no_level_works() {
+        for base in [SkillRoot::P { stat: Stat::DX, diff: DifficultyRating::S }, SkillRoot::M { stat: Stat::IQ, diff: DifficultyRating::S }] {
+            let s = Skill { rank: 2, ..skill("Special", base) };
+            for edition in [GurpsEd::Ed3, GurpsEd::Ed4] {
+                assert_eq!(None, s.level(&Config::default_tl8(edition)));
+            }
+        }
+    }
+}
@@ -0,0 +1,398 @@
+use std::{fmt::Display, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{adq::Adq, attrib::{AttributeType, AttributeValued}, ch::Ch, config::Config, context::Context, dta::genre::Genre, misc::{category::CategoryPayload, costly::Costly, named::Named}, secondary::PurchaseError, skill::{Skill, SkillLevel}};
+
+/**
+ Something a [Template] grants.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Grant {
+    /// Raise an attribute by `levels`.
+    Attribute { attrib: AttributeType, levels: i32 },
+    /// An advantage or disadvantage, at `level`.
+    Adq { name: String, #[serde(default = "default_one")] level: usize },
+    /// A skill, at (at least) `relative` level to its attribute, e.g. `1` for DX+1.
+    Skill { name: String, #[serde(default)] relative: i32 },
+    /// Some equipment.
+    Equipment { name: String, #[serde(default = "default_one")] quantity: usize },
+}
+
+fn default_one() -> usize { 1 }
+
+impl Display for Grant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Attribute { attrib, levels } => write!(f, "{attrib:?} {levels:+}"),
+            Self::Adq { name, level: 1 } => write!(f, "{name}"),
+            Self::Adq { name, level } => write!(f, "{name}@{level}"),
+            Self::Skill { name, relative } => write!(f, "{name} ({relative:+})"),
+            Self::Equipment { name, quantity: 1 } => write!(f, "{name}"),
+            Self::Equipment { name, quantity } => write!(f, "{name} ×{quantity}"),
+        }
+    }
+}
+
+/**
+ How much may be picked out of a [ChoiceGroup].
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum ChoiceLimit {
+    /// Options worth at most this many points &ndash; if negative, disadvantages worth at most that many.
+    Points(i32),
+    /// Exactly this many options.
+    Count(usize),
+}
+
+/**
+ An open choice &ndash; e.g. "20 points from the following" or "one of the following".
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ChoiceGroup {
+    #[serde(default)]
+    pub description: String,
+    pub limit: ChoiceLimit,
+    pub options: Vec<Grant>,
+}
+
+/**
+ Something a [Ch] must meet before a [Template] can be applied.
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum Prerequisite {
+    /// Attribute of at least `min`.
+    Attribute { attrib: AttributeType, min: i32 },
+    /// Has an advantage, disadvantage, package or skill of that name.
+    Has(String),
+    /// Has no advantage, disadvantage, package or skill of that name.
+    Lacks(String),
+}
+
+/**
+ Character template &ndash; unlike packages, not necessarily all-or-nothing: fixed grants along with open choices.
+
+ Loadable from JSON or TOML, see [Template::load].
+ */
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Template {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prerequisites: Vec<Prerequisite>,
+    /// Granted as is.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub grants: Vec<Grant>,
+    /// Choices to make, in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<ChoiceGroup>,
+}
+
+/**
+ Reasons why a [Template] (or some choice of it) can't be applied.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateError {
+    /// `Ch` doesn't meet a prerequisite.
+    Prerequisite(Prerequisite),
+    /// The genre has no advantage, skill, etc. of that name.
+    NotFound(String),
+    /// No rank gets the skill to the level asked for.
+    SkillLevel(String),
+    /// Attribute raise went out of bounds, etc.
+    Purchase(PurchaseError),
+    /// All choices have been made already.
+    NoOpenChoice,
+    /// No such option, or picked twice.
+    BadPick(usize),
+    /// Picked options are worth more points (of the limit's sign) than the limit allows.
+    OverPoints { limit: i32, spent: f64 },
+    /// Picked a different number of options than the limit says.
+    WrongCount { limit: usize, picked: usize },
+    /// Tried to finish with this many choices still open.
+    OpenChoices(usize),
+}
+
+impl From<PurchaseError> for TemplateError {
+    fn from(value: PurchaseError) -> Self {
+        Self::Purchase(value)
+    }
+}
+
+/**
+ See if `ch` has an advantage, disadvantage, package or skill called `name`.
+ */
+fn has(ch: &Ch, name: &str) -> bool {
+    ch.advantages.iter().chain(ch.packages.iter()).any(|a| a.name().eq_ignore_ascii_case(name))
+    || ch.skills.iter().any(|s| s.name().eq_ignore_ascii_case(name))
+}
+
+/**
+ Give `grant` to `ch`. Already owned advantages and skills are raised, never lowered.
+ */
+fn give(ch: &mut Ch, grant: &Grant, genre: &Genre, config: &Config) -> Result<(), TemplateError> {
+    match grant {
+        Grant::Attribute { attrib, levels } => { ch.raise_attribute(*attrib, *levels, genre.max_attr_default())?; },
+        Grant::Adq { name, level } => {
+            let adq = [Context::Advantage, Context::Disadvantage].iter()
+                .find_map(|c| match genre.find(c, name) {
+                    Some(CategoryPayload::Advantage(a) | CategoryPayload::Disadvantage(a)) => Some(a.clone()),
+                    _ => None
+                })
+                .ok_or_else(|| TemplateError::NotFound(name.clone()))?;
+            match ch.advantages.iter_mut().find(|a| a.name == adq.name) {
+                Some(a) => a.level = a.level.max(*level),
+                None => ch.advantages.push(Adq { level: *level, ..adq })
+            }
+        },
+        Grant::Skill { name, relative } => {
+            let Some(CategoryPayload::Skill(skill)) = genre.find(&Context::Skill, name) else {
+                return Err(TemplateError::NotFound(name.clone()));
+            };
+            let (rank, _) = skill.rank_reaching(*relative, |s| s.level(config))
+                .ok_or_else(|| TemplateError::SkillLevel(name.clone()))?;
+            match ch.skills.iter_mut().find(|s| s.name == skill.name) {
+                Some(s) => s.rank = s.rank.max(rank),
+                None => ch.skills.push(Skill { rank, ..skill.clone() })
+            }
+        },
+        Grant::Equipment { name, quantity } => {
+            let e = genre.find_equipment(name).ok_or_else(|| TemplateError::NotFound(name.clone()))?;
+            ch.inventory.add(e.clone(), *quantity);
+        },
+    }
+    Ok(())
+}
+
+impl Template {
+    /**
+     Parse a template from JSON.
+     */
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| e.to_string())
+    }
+
+    /**
+     Parse a template from TOML.
+     */
+    pub fn from_toml(toml: &str) -> Result<Self, String> {
+        toml::from_str(toml).map_err(|e| e.to_string())
+    }
+
+    /**
+     Load a template from file; `.toml` files are read as TOML, anything else as JSON.
+     */
+    pub fn load(filename: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(filename)
+            .map_err(|e| format!("{}: {e}", filename.display()))?;
+        match filename.extension().and_then(|x| x.to_str()) {
+            Some(x) if x.eq_ignore_ascii_case("toml") => Self::from_toml(&content),
+            _ => Self::from_json(&content)
+        }.map_err(|e| format!("{}: {e}", filename.display()))
+    }
+
+    /**
+     Start applying the template to (a copy of) `ch` &ndash; prerequisites are checked and fixed grants given.
+
+     **Returns** a session to walk through the open choices with, see [TemplateSession::choose].
+     */
+    pub fn apply<'a>(&'a self, ch: &Ch, genre: &'a Genre, config: &'a Config) -> Result<TemplateSession<'a>, TemplateError> {
+        for p in &self.prerequisites {
+            let met = match p {
+                Prerequisite::Attribute { attrib, min } => ch.attribute(*attrib).value() >= *min,
+                Prerequisite::Has(name) => has(ch, name),
+                Prerequisite::Lacks(name) => !has(ch, name),
+            };
+            if !met {
+                return Err(TemplateError::Prerequisite(p.clone()));
+            }
+        }
+        let mut ch = ch.clone();
+        for grant in &self.grants {
+            give(&mut ch, grant, genre, config)?;
+        }
+        Ok(TemplateSession { template: self, genre, config, ch, next: 0 })
+    }
+}
+
+/**
+ A [Template] being applied, one choice group at a time.
+ */
+pub struct TemplateSession<'a> {
+    template: &'a Template,
+    genre: &'a Genre,
+    config: &'a Config,
+    ch: Ch,
+    next: usize,
+}
+
+impl TemplateSession<'_> {
+    /**
+     Get the `Ch` as it stands so far.
+     */
+    pub fn ch(&self) -> &Ch {
+        &self.ch
+    }
+
+    /**
+     Get the choice group to be made next, if any is left.
+     */
+    pub fn current(&self) -> Option<&ChoiceGroup> {
+        self.template.choices.get(self.next)
+    }
+
+    /**
+     Get the current group's options along with their point costs &ndash; `None` for those which can't be taken.
+     */
+    pub fn options(&self) -> Vec<(&Grant, Option<f64>)> {
+        let Some(group) = self.current() else { return vec![] };
+        group.options.iter().map(|o| {
+            let mut ch = self.ch.clone();
+            (o, give(&mut ch, o, self.genre, self.config).ok().map(|_| ch.cost() - self.ch.cost()))
+        }).collect()
+    }
+
+    /**
+     Make the current choice by picking options (by index) within the group's limit.
+
+     On error nothing changes and the same choice stays open.
+     */
+    pub fn choose(&mut self, picks: &[usize]) -> Result<(), TemplateError> {
+        let group = self.current().ok_or(TemplateError::NoOpenChoice)?;
+        for (i, p) in picks.iter().enumerate() {
+            if *p >= group.options.len() || picks[..i].contains(p) {
+                return Err(TemplateError::BadPick(*p));
+            }
+        }
+        let mut ch = self.ch.clone();
+        for p in picks {
+            give(&mut ch, &group.options[*p], self.genre, self.config)?;
+        }
+        match group.limit {
+            ChoiceLimit::Points(limit) => {
+                let spent = ch.cost() - self.ch.cost();
+                let sign = if limit < 0 { -1.0 } else { 1.0 };
+                if spent * sign > (limit as f64).abs() {
+                    return Err(TemplateError::OverPoints { limit, spent });
+                }
+            },
+            ChoiceLimit::Count(limit) => if picks.len() != limit {
+                return Err(TemplateError::WrongCount { limit, picked: picks.len() });
+            },
+        }
+        self.ch = ch;
+        self.next += 1;
+        Ok(())
+    }
+
+    /**
+     Finish applying the template.
+
+     **Returns** the resulting `Ch`, or an error if there are choices still open.
+     */
+    pub fn finish(self) -> Result<Ch, TemplateError> {
+        match self.template.choices.len() - self.next {
+            0 => Ok(self.ch),
+            n => Err(TemplateError::OpenChoices(n))
+        }
+    }
+}
+
+#[cfg(test)]
+mod template_tests {
+    use crate::{attrib::{AttributeType, AttributeValued}, ch::Ch, config::Config, context::Context, dta::genre::Genre, edition::GurpsEd, fixtures::{self, adq, skill}, misc::category::CategoryPayload, skill::{DifficultyRating, SkillRoot, Stat}};
+
+    use super::{Grant, Prerequisite, Template, TemplateError};
+
+    fn genre() -> Genre {
+        let skill = |name| CategoryPayload::Skill(skill(name, SkillRoot::P { stat: Stat::DX, diff: DifficultyRating::A }));
+        fixtures::genre(vec![
+            (Context::Skill, "Combat", vec![("Broadsword", skill("Broadsword")), ("Axe/Mace", skill("Axe/Mace")), ("Shield", skill("Shield"))]),
+            (Context::Advantage, "Physical", vec![("Toughness", CategoryPayload::Advantage(adq("Toughness", 10))), ("Alertness", CategoryPayload::Advantage(adq("Alertness", 5)))]),
+            (Context::Disadvantage, "Mental", vec![("Honesty", CategoryPayload::Disadvantage(adq("Honesty", -10)))]),
+        ])
+    }
+
+    const KNIGHT: &str = r#"
+        name = "Knight"
+        prerequisites = [{ Attribute = { attrib = "ST", min = 11 } }, { Lacks = "Honesty" }]
+        grants = [{ Attribute = { attrib = "ST", levels = 1 } }, { Skill = { name = "Shield" } }]
+
+        [[choices]]
+        description = "10 points of advantages"
+        limit = { Points = 10 }
+        options = [{ Adq = { name = "Toughness" } }, { Adq = { name = "Alertness", level = 2 } }, { Adq = { name = "Alertness" } }]
+
+        [[choices]]
+        description = "One of"
+        limit = { Count = 1 }
+        options = [{ Skill = { name = "Broadsword", relative = 1 } }, { Skill = { name = "Axe/Mace", relative = 1 } }]
+    "#;
+
+    #[test]
+    fn walking_through_choices_works() {
+        let (genre, config) = (genre(), Config::default_tl8(GurpsEd::Ed4));
+        let template = Template::from_toml(KNIGHT).unwrap();
+        let mut ch = Ch::new("Squire");
+        assert_eq!(Err(TemplateError::Prerequisite(Prerequisite::Attribute { attrib: AttributeType::ST, min: 11 })),
+            template.apply(&ch, &genre, &config).map(|_| ()));
+        ch.st += 1;
+
+        let mut session = template.apply(&ch, &genre, &config).unwrap();
+        assert_eq!(2, session.ch().skills[0].rank);
+        assert_eq!(vec![Some(10.0), Some(10.0), Some(5.0)], session.options().iter().map(|o| o.1).collect::<Vec<_>>());
+        assert_eq!("Alertness@2", session.options()[1].0.to_string());
+        assert_eq!(Err(TemplateError::OverPoints { limit: 10, spent: 15.0 }), session.choose(&[0, 2]));
+        assert_eq!(Err(TemplateError::BadPick(2)), session.choose(&[2, 2]));
+        session.choose(&[2]).unwrap();
+
+        assert_eq!(Err(TemplateError::WrongCount { limit: 1, picked: 2 }), session.choose(&[0, 1]));
+        session.choose(&[1]).unwrap();
+        assert_eq!(Err(TemplateError::NoOpenChoice), session.choose(&[0]));
+
+        let knight = session.finish().unwrap();
+        assert_eq!(1, knight.advantage_level("Alertness"));
+        assert_eq!(Some(&Grant::Skill { name: "Axe/Mace".into(), relative: 1 }), template.choices[1].options.get(1));
+        assert_eq!(("Axe/Mace", 3), (knight.skills[1].name.as_str(), knight.skills[1].rank));
+        assert_eq!(12, knight.st.value());
+        assert!(ch.skills.is_empty());
+    }
+
+    #[test]
+    fn disadvantage_choices_work() {
+        let (genre, config) = (genre(), Config::default_tl8(GurpsEd::Ed4));
+        let template = Template::from_toml(r#"
+            name = "Outlaw"
+
+            [[choices]]
+            description = "-15 points of disadvantages"
+            limit = { Points = -15 }
+            options = [{ Adq = { name = "Honesty", level = 2 } }, { Adq = { name = "Honesty" } }]
+        "#).unwrap();
+        let mut session = template.apply(&Ch::new("Bandit"), &genre, &config).unwrap();
+        assert_eq!(Err(TemplateError::OverPoints { limit: -15, spent: -20.0 }), session.choose(&[0]));
+        session.choose(&[1]).unwrap();
+        assert_eq!(1, session.finish().unwrap().advantage_level("Honesty"));
+    }
+
+    #[test]
+    fn unmet_and_unknown_are_reported() {
+        let (genre, config) = (genre(), Config::default_tl8(GurpsEd::Ed4));
+        let mut template = Template::from_toml(KNIGHT).unwrap();
+        let mut ch = Ch::new("Squire");
+        ch.st += 1;
+        let mut honest = adq("Honesty", -10);
+        honest.level = 1;
+        ch.advantages.push(honest);
+        assert_eq!(Err(TemplateError::Prerequisite(Prerequisite::Lacks("Honesty".into()))),
+            template.apply(&ch, &genre, &config).map(|_| ()));
+        ch.advantages.clear();
+
+        assert_eq!(Err(TemplateError::OpenChoices(2)), template.apply(&ch, &genre, &config).unwrap().finish().map(|_| ()));
+        template.grants.push(Grant::Equipment { name: "Warhorse".into(), quantity: 1 });
+        assert_eq!(Err(TemplateError::NotFound("Warhorse".into())), template.apply(&ch, &genre, &config).map(|_| ()));
+        template.grants.pop();
+        template.grants.push(Grant::Skill { name: "Shield".into(), relative: 99 });
+        assert_eq!(Err(TemplateError::SkillLevel("Shield".into())), template.apply(&ch, &genre, &config).map(|_| ()));
+        assert_eq!(template, Template::from_json(&serde_json::to_string(&template).unwrap()).unwrap());
+    }
+}